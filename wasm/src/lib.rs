@@ -1,12 +1,26 @@
 use argon2::{
-    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2, Params,
+    password_hash::{
+        rand_core::{OsRng, RngCore}, Output, ParamsString, PasswordHash, PasswordVerifier, Salt,
+        SaltString,
+    },
+    Argon2, AssociatedData, Block, Params, ParamsBuilder,
 };
-use wasm_bindgen::prelude::*;
-use log::error;
+use wasm_bindgen::{prelude::*, JsCast};
+use log::{error, warn};
 use serde::{Serialize, Deserialize};
 use thiserror::Error;
-use js_sys::Object;
+use js_sys::{Object, Uint8Array};
+use base64ct::{Base64, Base64Unpadded, Base64Url, Base64UrlUnpadded, Encoding};
+use blake2::{Blake2b512, Blake2bMac512, Digest};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use unicode_normalization::UnicodeNormalization;
+use zeroize::Zeroizing;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
 
 /// Custom error type for password hashing and verification operations
 #[derive(Error, Debug)]
@@ -17,9 +31,101 @@ pub enum PasswordError {
     InvalidParams(String),
     #[error("Invalid password or hash: {0}")]
     InvalidInput(String),
+    #[error("Resource exhausted: {0}")]
+    ResourceExhausted(String),
+    #[error("Random number generator unavailable: {0}")]
+    RngUnavailable(String),
+    #[error("Malformed hash salt: {0}")]
+    BadSalt(String),
+    #[error("Malformed hash output: {0}")]
+    BadTag(String),
+    #[error("Malformed hash parameters: {0}")]
+    BadParams(String),
+    #[error("Malformed hash algorithm identifier: {0}")]
+    BadAlgorithm(String),
+    #[error("Unsupported hash algorithm: {0}")]
+    UnsupportedAlgorithm(String),
+    #[error("Inconsistent tag length: {0}")]
+    InconsistentTagLength(String),
+    #[error("Password does not meet policy: {}", .0.join(", "))]
+    WeakPassword(Vec<String>),
 }
 
-// Convert PasswordError to a JavaScript object with { message: String }
+impl PasswordError {
+    /// Stable, machine-readable identifier for the error variant
+    pub fn code(&self) -> &'static str {
+        match self {
+            PasswordError::Serialization(_) => "SERIALIZATION",
+            PasswordError::InvalidParams(_) => "INVALID_PARAMS",
+            PasswordError::InvalidInput(_) => "INVALID_INPUT",
+            PasswordError::ResourceExhausted(_) => "RESOURCE_EXHAUSTED",
+            PasswordError::RngUnavailable(_) => "RNG_UNAVAILABLE",
+            PasswordError::BadSalt(_) => "BAD_SALT",
+            PasswordError::BadTag(_) => "BAD_TAG",
+            PasswordError::BadParams(_) => "BAD_PARAMS",
+            PasswordError::BadAlgorithm(_) => "BAD_ALGORITHM",
+            PasswordError::UnsupportedAlgorithm(_) => "UNSUPPORTED_ALGORITHM",
+            PasswordError::InconsistentTagLength(_) => "INCONSISTENT_TAG_LENGTH",
+            PasswordError::WeakPassword(_) => "WEAK_PASSWORD",
+        }
+    }
+
+    /// Whether the same call may succeed if retried later
+    ///
+    /// True for transient host conditions (no entropy yet, memory pressure),
+    /// false for errors in the inputs, which will fail the same way again.
+    pub fn retryable(&self) -> bool {
+        matches!(self, PasswordError::ResourceExhausted(_) | PasswordError::RngUnavailable(_))
+    }
+
+    /// Prefixes the message with the position of the failing item in a batch
+    fn at_index(self, index: usize) -> Self {
+        let prefix = |msg: String| format!("entry {}: {}", index, msg);
+        match self {
+            PasswordError::Serialization(err) => {
+                PasswordError::Serialization(serde_wasm_bindgen::Error::new(prefix(err.to_string())))
+            }
+            PasswordError::InvalidParams(msg) => PasswordError::InvalidParams(prefix(msg)),
+            PasswordError::InvalidInput(msg) => PasswordError::InvalidInput(prefix(msg)),
+            PasswordError::ResourceExhausted(msg) => PasswordError::ResourceExhausted(prefix(msg)),
+            PasswordError::RngUnavailable(msg) => PasswordError::RngUnavailable(prefix(msg)),
+            PasswordError::BadSalt(msg) => PasswordError::BadSalt(prefix(msg)),
+            PasswordError::BadTag(msg) => PasswordError::BadTag(prefix(msg)),
+            PasswordError::BadParams(msg) => PasswordError::BadParams(prefix(msg)),
+            PasswordError::BadAlgorithm(msg) => PasswordError::BadAlgorithm(prefix(msg)),
+            PasswordError::UnsupportedAlgorithm(msg) => PasswordError::UnsupportedAlgorithm(prefix(msg)),
+            PasswordError::InconsistentTagLength(msg) => PasswordError::InconsistentTagLength(prefix(msg)),
+            // The rules are joined into the message, so the index goes in front of the first one
+            PasswordError::WeakPassword(mut rules) => {
+                if let Some(first) = rules.first_mut() {
+                    *first = prefix(std::mem::take(first));
+                }
+                PasswordError::WeakPassword(rules)
+            }
+        }
+    }
+}
+
+/// Serializable form of a `PasswordError` for results that report errors as data
+#[derive(Serialize, Deserialize)]
+pub struct ErrorInfo {
+    pub message: String,
+    pub code: String,
+    #[serde(default)]
+    pub retryable: bool,
+}
+
+impl From<&PasswordError> for ErrorInfo {
+    fn from(err: &PasswordError) -> Self {
+        ErrorInfo {
+            message: err.to_string(),
+            code: err.code().to_string(),
+            retryable: err.retryable(),
+        }
+    }
+}
+
+// Convert PasswordError to a JavaScript object with { message: String, code: String, retryable: bool }
 impl From<PasswordError> for JsValue {
     fn from(err: PasswordError) -> Self {
         let obj = Object::new();
@@ -29,6 +135,16 @@ impl From<PasswordError> for JsValue {
             &JsValue::from_str("message"),
             &JsValue::from_str(&message),
         ).expect("Failed to set error message");
+        js_sys::Reflect::set(
+            &obj,
+            &JsValue::from_str("code"),
+            &JsValue::from_str(err.code()),
+        ).expect("Failed to set error code");
+        js_sys::Reflect::set(
+            &obj,
+            &JsValue::from_str("retryable"),
+            &JsValue::from_bool(err.retryable()),
+        ).expect("Failed to set error retryable flag");
         obj.into()
     }
 }
@@ -40,12 +156,216 @@ impl From<argon2::Error> for PasswordError {
     }
 }
 
+/// Argon2 variant, exported to JavaScript as a numeric enum
+///
+/// Options also accept the variant name as a string (`"argon2id"`,
+/// `"argon2i"`, `"argon2d"`) for callers that do not import the enum.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    Argon2id = 0,
+    Argon2i = 1,
+    Argon2d = 2,
+}
+
+impl From<Algorithm> for argon2::Algorithm {
+    fn from(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Argon2id => argon2::Algorithm::Argon2id,
+            Algorithm::Argon2i => argon2::Algorithm::Argon2i,
+            Algorithm::Argon2d => argon2::Algorithm::Argon2d,
+        }
+    }
+}
+
+impl Serialize for Algorithm {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(*self as u32)
+    }
+}
+
+impl<'de> Deserialize<'de> for Algorithm {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AlgorithmVisitor;
+
+        impl serde::de::Visitor<'_> for AlgorithmVisitor {
+            type Value = Algorithm;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("an Algorithm enum value or one of \"argon2id\", \"argon2i\", \"argon2d\"")
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<Algorithm, E> {
+                match value {
+                    0 => Ok(Algorithm::Argon2id),
+                    1 => Ok(Algorithm::Argon2i),
+                    2 => Ok(Algorithm::Argon2d),
+                    _ => Err(E::invalid_value(serde::de::Unexpected::Unsigned(value), &self)),
+                }
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<Algorithm, E> {
+                u64::try_from(value)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Signed(value), &self))
+                    .and_then(|value| self.visit_u64(value))
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, value: f64) -> Result<Algorithm, E> {
+                if value.fract() != 0.0 || value < 0.0 {
+                    return Err(E::invalid_value(serde::de::Unexpected::Float(value), &self));
+                }
+                self.visit_u64(value as u64)
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Algorithm, E> {
+                match value {
+                    "argon2id" => Ok(Algorithm::Argon2id),
+                    "argon2i" => Ok(Algorithm::Argon2i),
+                    "argon2d" => Ok(Algorithm::Argon2d),
+                    _ => Err(E::invalid_value(serde::de::Unexpected::Str(value), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(AlgorithmVisitor)
+    }
+}
+
 /// Options for configuring Argon2 hashing
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct HashOptions {
     pub time_cost: u32,
     pub memory_cost: u32,
     pub parallelism: u32,
+    /// Bucket size the password is padded to before hashing; must match at verify time
+    #[serde(default)]
+    pub pad_password_to: Option<u32>,
+    /// Argon2 variant, defaults to Argon2id
+    #[serde(default)]
+    pub algorithm: Option<Algorithm>,
+    /// Permits hashing an empty password, e.g. for guest accounts
+    #[serde(default)]
+    pub allow_empty: bool,
+    /// Length in bytes of the generated salt, defaults to 16
+    #[serde(default)]
+    pub salt_len: Option<u32>,
+    /// Domain-separation context mixed into the password via HKDF; must match at verify time
+    #[serde(default)]
+    pub context: Option<String>,
+    /// Length in bytes of the hash output, defaults to 32
+    #[serde(default)]
+    pub output_len: Option<u32>,
+    /// Treats `parallelism: 0` as "one lane per core", clamped to 1..=8
+    #[serde(default)]
+    pub parallelism_auto: bool,
+    /// Rotation epoch bound into the hash as associated data; read it back with `current_epoch`
+    #[serde(default)]
+    pub epoch: Option<u32>,
+}
+
+impl Default for HashOptions {
+    fn default() -> Self {
+        HashOptions {
+            time_cost: Params::DEFAULT_T_COST,
+            memory_cost: Params::DEFAULT_M_COST,
+            parallelism: Params::DEFAULT_P_COST,
+            pad_password_to: None,
+            algorithm: None,
+            allow_empty: false,
+            salt_len: None,
+            context: None,
+            output_len: None,
+            parallelism_auto: false,
+            epoch: None,
+        }
+    }
+}
+
+/// Options for verifying a password hashed with non-default preprocessing
+///
+/// These must match the options the hash was created with.
+#[derive(Serialize, Deserialize, Default)]
+pub struct VerifyOptions {
+    #[serde(default)]
+    pub pad_password_to: Option<u32>,
+    /// Permits verifying an empty password against a hash made with `allow_empty`
+    #[serde(default)]
+    pub allow_empty: bool,
+    #[serde(default)]
+    pub context: Option<String>,
+    /// Expected tag length in bytes; hashes whose decoded tag differs are rejected
+    #[serde(default)]
+    pub output_len: Option<u32>,
+}
+
+/// Shared accounting for the optional memory budget across hash calls
+struct MemoryBudget {
+    limit: Option<u64>,
+    in_use: u64,
+}
+
+static MEMORY_BUDGET: Mutex<MemoryBudget> = Mutex::new(MemoryBudget {
+    limit: None,
+    in_use: 0,
+});
+
+/// Cached `verify_cached` outcome
+struct CachedVerification {
+    valid: bool,
+    expires_at: f64,
+}
+
+/// Upper bound on cached verifications; the soonest-expiring entry is evicted past it
+const MAX_VERIFY_CACHE_ENTRIES: usize = 1024;
+
+/// Results cached by `verify_cached`, keyed by a keyed Blake2b MAC of the hash and password
+static VERIFY_CACHE: Mutex<BTreeMap<[u8; 64], CachedVerification>> = Mutex::new(BTreeMap::new());
+
+/// Random key for `verify_cache_key`, drawn on first use and dropped by `reset_state`
+///
+/// Without it the cache keys would be a fast unkeyed digest of the password,
+/// letting anyone who can read memory skip Argon2 when guessing.
+static VERIFY_CACHE_SECRET: Mutex<Option<[u8; 32]>> = Mutex::new(None);
+
+/// Named parameter presets registered with `register_preset`, in registration order
+static PRESETS: Mutex<Vec<(String, HashOptions)>> = Mutex::new(Vec::new());
+
+thread_local! {
+    /// Callback registered with `set_event_sink`; JS functions are not `Send`,
+    /// so this is per-thread rather than behind a `Mutex`
+    static EVENT_SINK: RefCell<Option<js_sys::Function>> = const { RefCell::new(None) };
+}
+
+/// Reservation against the memory budget, released when dropped
+struct MemoryReservation {
+    bytes: u64,
+}
+
+impl MemoryReservation {
+    fn acquire(bytes: u64) -> Result<Self, PasswordError> {
+        let mut budget = MEMORY_BUDGET.lock().unwrap_or_else(|err| err.into_inner());
+
+        if let Some(limit) = budget.limit
+            && budget.in_use + bytes > limit
+        {
+            return Err(PasswordError::ResourceExhausted(format!(
+                "hash needs {} bytes but only {} of the {} byte memory budget are free",
+                bytes,
+                limit.saturating_sub(budget.in_use),
+                limit
+            )));
+        }
+
+        budget.in_use += bytes;
+        Ok(MemoryReservation { bytes })
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        let mut budget = MEMORY_BUDGET.lock().unwrap_or_else(|err| err.into_inner());
+        budget.in_use = budget.in_use.saturating_sub(self.bytes);
+    }
 }
 
 /// Request struct for password verification
@@ -55,29 +375,314 @@ pub struct VerifyRequest {
     pub password: String,
 }
 
+/// Result of comparing the parameter strength of two hashes
+#[derive(Serialize, Deserialize)]
+pub struct StrengthComparison {
+    pub stronger: String,
+    pub memory_delta: i64,
+    pub time_delta: i64,
+}
+
+/// Result of a verification that also reports how long it took
+#[derive(Serialize, Deserialize)]
+pub struct TimedVerification {
+    pub valid: bool,
+    pub elapsed_ms: f64,
+}
+
+/// Hash split into separately stored parameters and keyed verifier tag
+#[derive(Serialize, Deserialize)]
+pub struct SplitStorageHash {
+    pub public_params: String,
+    pub verifier_b64: String,
+}
+
+/// Results of a batch verification with the time spent on each item
+#[derive(Serialize, Deserialize)]
+pub struct TimedBatchVerification {
+    pub results: Vec<bool>,
+    pub total_ms: f64,
+    pub per_item_ms: Vec<f64>,
+}
+
+/// Result of a verification checked against a denylist of leaked hashes
+#[derive(Serialize, Deserialize)]
+pub struct DenylistVerification {
+    pub valid: bool,
+    pub compromised: bool,
+}
+
+/// Result of a verification that also flags hashes using an old Argon2 version
+#[derive(Serialize, Deserialize)]
+pub struct VersionedVerification {
+    pub valid: bool,
+    pub outdated_version: bool,
+}
+
+/// Outcome of `try_verify`, which reports failures as data instead of throwing
+#[derive(Serialize, Deserialize)]
+pub struct TryVerifyResult {
+    pub ok: bool,
+    pub valid: bool,
+    pub error: Option<ErrorInfo>,
+}
+
+/// Recommended parameters for a device together with their expected hash time
+#[derive(Serialize, Deserialize)]
+pub struct ParamRecommendation {
+    pub time_cost: u32,
+    pub memory_cost: u32,
+    pub parallelism: u32,
+    pub estimated_ms: f64,
+}
+
+/// Summary of the algorithms and versions used across a set of stored hashes
+#[derive(Serialize, Deserialize)]
+pub struct HomogeneityAudit {
+    pub algorithms: Vec<String>,
+    pub versions: Vec<u32>,
+    pub homogeneous: bool,
+    pub malformed_count: u32,
+}
+
+/// Split of a param set's cost between memory and time, from `cost_breakdown`
+#[derive(Serialize, Deserialize)]
+pub struct CostBreakdown {
+    /// `log2(m)`, the bits of work contributed by memory
+    pub memory_component: f64,
+    /// `log2(t)`, the bits of work contributed by passes
+    pub time_component: f64,
+    /// `p`, the best-case wall-clock speedup from running lanes in parallel
+    pub parallelism_divisor: u32,
+    /// Share of the total work bits that comes from memory, in `(0, 1]`
+    pub relative_memory_vs_time: f64,
+}
+
+/// Params to substitute for a stored hash's own in `verify_with_param_override`
+#[derive(Serialize, Deserialize, Default)]
+pub struct ParamOverride {
+    #[serde(default)]
+    pub memory_cost: Option<u32>,
+    #[serde(default)]
+    pub time_cost: Option<u32>,
+    #[serde(default)]
+    pub parallelism: Option<u32>,
+}
+
+/// Number of stored hashes sharing one algorithm, version and param set
+#[derive(Serialize, Deserialize)]
+pub struct ParamBucket {
+    pub algorithm: String,
+    pub version: u32,
+    pub memory_cost: u32,
+    pub time_cost: u32,
+    pub parallelism: u32,
+    pub count: u32,
+}
+
+/// Distribution of params across a set of stored hashes
+#[derive(Serialize, Deserialize)]
+pub struct ParamHistogram {
+    pub buckets: Vec<ParamBucket>,
+    pub malformed: u32,
+}
+
+/// Result of a verification along with an estimate of the work it cost
+#[derive(Serialize, Deserialize)]
+pub struct MeteredVerification {
+    pub valid: bool,
+    pub compute_units: f64,
+}
+
+/// Result of a verification checked against a minimum parameter policy
+#[derive(Serialize, Deserialize)]
+pub struct PolicyVerification {
+    pub valid: bool,
+    pub below_policy: bool,
+}
+
+/// Result of a verification that refuses hashes over a memory ceiling
+#[derive(Serialize, Deserialize)]
+pub struct BoundedVerification {
+    pub valid: bool,
+    pub rejected: bool,
+    pub reason: Option<String>,
+}
+
+/// Result of a verification along with the algorithm and version the hash declares
+#[derive(Serialize, Deserialize)]
+pub struct IdentifiedVerification {
+    pub valid: bool,
+    pub algorithm: String,
+    pub version: u32,
+}
+
+/// Hash produced by `hash_adaptive` along with the options it settled on
+#[derive(Serialize, Deserialize)]
+pub struct AdaptiveHash {
+    pub hash: String,
+    pub options_used: HashOptions,
+}
+
+/// Result of a verification that also checks a timestamped hash's age
+#[derive(Serialize, Deserialize)]
+pub struct FreshVerification {
+    pub valid: bool,
+    pub expired: bool,
+}
+
+/// Policy applied by `login_check`; every rule is optional
+#[derive(Serialize, Deserialize, Default)]
+pub struct LoginPolicy {
+    /// Params new hashes should use; a valid hash with different params needs a rehash
+    #[serde(default)]
+    pub target: Option<HashOptions>,
+    /// Floor below which a credential must be reset
+    #[serde(default)]
+    pub min_memory_cost: Option<u32>,
+    #[serde(default)]
+    pub min_time_cost: Option<u32>,
+    /// Maximum age of a `hash_timestamped` hash, checked against `now_unix`
+    #[serde(default)]
+    pub max_age_days: Option<f64>,
+    #[serde(default)]
+    pub now_unix: Option<f64>,
+}
+
+/// Outcome of `login_check`
+#[derive(Serialize, Deserialize)]
+pub struct LoginDecision {
+    pub valid: bool,
+    pub action: String,
+}
+
+/// Strength rules checked by `hash_with_policy`; every rule is optional
+#[derive(Serialize, Deserialize, Default)]
+pub struct PasswordPolicy {
+    /// Minimum length in characters
+    #[serde(default)]
+    pub min_length: Option<u32>,
+    #[serde(default)]
+    pub require_upper: bool,
+    #[serde(default)]
+    pub require_digit: bool,
+    /// Requires a character that is neither alphanumeric nor whitespace
+    #[serde(default)]
+    pub require_symbol: bool,
+}
+
+/// Outcome of `verify_encodings`
+#[derive(Serialize, Deserialize)]
+pub struct EncodingMatch {
+    pub valid: bool,
+    /// Transform that produced the hashed input; `None` when the raw password matched
+    pub matched_encoding: Option<String>,
+}
+
+/// Event passed to the `set_event_sink` callback; never carries the password
+#[derive(Serialize, Deserialize)]
+pub struct DiagnosticEvent {
+    /// `"hash_started"`, `"hash_completed"` or `"verify_mismatch"`
+    pub event: String,
+    pub memory_cost: u32,
+    pub time_cost: u32,
+    pub parallelism: u32,
+    #[serde(default)]
+    pub elapsed_ms: Option<f64>,
+}
+
+/// Outcome of `verify_with_deadline`; `valid` is only set when `completed`
+#[derive(Serialize, Deserialize)]
+pub struct DeadlineVerification {
+    pub completed: bool,
+    #[serde(default)]
+    pub valid: Option<bool>,
+}
+
+/// Measured cost of moving from one param set to another, from `latency_delta`
+#[derive(Serialize, Deserialize)]
+pub struct LatencyDelta {
+    pub old_ms: f64,
+    pub new_ms: f64,
+    /// `new_ms / old_ms`; above 1 means the new params are slower
+    pub factor: f64,
+}
+
+/// Symmetric JSON Web Key as accepted by `crypto.subtle.importKey("jwk", ...)`
+#[derive(Serialize, Deserialize)]
+pub struct Jwk {
+    pub kty: String,
+    /// Unpadded base64url key bytes
+    pub k: String,
+    pub alg: String,
+}
+
+/// Which optional PHC fields a parsed hash carries
+#[derive(Serialize, Deserialize)]
+pub struct FieldPresence {
+    pub has_version: bool,
+    pub has_keyid: bool,
+    pub has_data: bool,
+    pub has_salt: bool,
+    pub has_output: bool,
+}
+
+/// Inclusive range accepted for a single parameter
+#[derive(Serialize, Deserialize)]
+pub struct Bounds {
+    pub min: u32,
+    pub max: u32,
+}
+
+/// Limits enforced on hashing options
+#[derive(Serialize, Deserialize)]
+pub struct ParamBounds {
+    pub memory_cost: Bounds,
+    pub time_cost: Bounds,
+    pub parallelism: Bounds,
+    pub salt_len: Bounds,
+    pub output_len: Bounds,
+}
+
+/// Canonical (password, options, hash) triple for downstream test suites
+#[cfg(feature = "test-vectors")]
+#[derive(Serialize, Deserialize)]
+pub struct TestVector {
+    pub password: String,
+    pub options: HashOptions,
+    pub hash: String,
+}
+
 /// Hashes a password using Argon2id with optional configuration
 #[wasm_bindgen]
 pub fn hash(password: &str, options: JsValue) -> Result<String, JsValue> {
+    let opts = parse_hash_options(options)?;
+
     // Input validation
-    if password.is_empty() {
+    if password.is_empty() && !opts.as_ref().is_some_and(|opts| opts.allow_empty) {
         return Err(PasswordError::InvalidInput("Password cannot be empty".to_string()).into());
     }
 
-    let opts: Option<HashOptions> = serde_wasm_bindgen::from_value(options)
-        .map_err(|err| {
-            error!("Failed to deserialize options: {}", err);
-            PasswordError::Serialization(err)
-        })?;
-
-    argon2id_hash(password, opts).map_err(|err| {
+    argon2id_hash(password.as_bytes(), opts).map_err(|err| {
         error!("Failed to hash password: {}", err);
         err.into()
     })
 }
 
 /// Verifies a password against a stored hash
+///
+/// Accepts standard PHC strings as well as the `$argon2id-hex$` layout
+/// produced by `hash_hex`. Surrounding ASCII whitespace, such as a trailing
+/// newline from a file, is ignored.
 #[wasm_bindgen]
 pub fn verify(hash: &str, password: &str) -> Result<bool, JsValue> {
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+
+    verify_normalized(&hash, password)
+}
+
+/// Internal function to verify against a hash already passed through `normalize_hash`
+fn verify_normalized(hash: &str, password: &str) -> Result<bool, JsValue> {
     // Input validation
     if hash.is_empty() || password.is_empty() {
         return Err(PasswordError::InvalidInput("Hash and password cannot be empty".to_string()).into());
@@ -94,51 +699,3656 @@ pub fn verify(hash: &str, password: &str) -> Result<bool, JsValue> {
     })
 }
 
-/// Internal function to hash a password with Argon2id
-fn argon2id_hash(password: &str, options: Option<HashOptions>) -> Result<String, PasswordError> {
-    let salt = SaltString::generate(&mut OsRng);
+/// Verifies a `{ hash, password }` request object
+#[wasm_bindgen]
+pub fn verify_request(req: JsValue) -> Result<bool, JsValue> {
+    let request: VerifyRequest = serde_wasm_bindgen::from_value(req).map_err(|err| {
+        error!("Failed to parse verify request: {}", err);
+        JsValue::from(PasswordError::Serialization(err))
+    })?;
 
-    let argon2 = match options {
-        Some(opts) => {
-            // Validate parameters to prevent invalid configurations
-            if opts.memory_cost < 8 || opts.time_cost == 0 || opts.parallelism == 0 {
-                return Err(PasswordError::InvalidInput(
-                    "Invalid hash parameters: memory_cost must be >= 8, time_cost and parallelism must be > 0".to_string(),
-                ));
-            }
+    verify(&request.hash, &request.password)
+}
+
+/// Verifies a password against a hash created with preprocessing options
+#[wasm_bindgen]
+pub fn verify_with_options(hash: &str, password: &str, options: JsValue) -> Result<bool, JsValue> {
+    let opts: Option<VerifyOptions> = serde_wasm_bindgen::from_value(options).map_err(|err| {
+        error!("Failed to deserialize options: {}", err);
+        PasswordError::Serialization(err)
+    })?;
+    let opts = opts.unwrap_or_default();
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+
+    if hash.is_empty() || (password.is_empty() && !opts.allow_empty) {
+        return Err(PasswordError::InvalidInput("Hash and password cannot be empty".to_string()).into());
+    }
+
+    let password = preprocess_password(password.as_bytes(), opts.pad_password_to, opts.context.as_deref())?;
+
+    check_tag_len(&hash, opts.output_len)
+        .and_then(|_| argon2id_verify_bytes(&hash, &password))
+        .map_err(|err| {
+            error!("Failed to verify password: {}", err);
+            err.into()
+        })
+}
+
+/// Caps the total Argon2 memory that concurrent hash calls may use, in bytes
+///
+/// Each `hash` call reserves its `memory_cost` against the budget for the
+/// duration of the computation and fails with a resource-exhausted error if
+/// the reservation does not fit.
+#[wasm_bindgen]
+pub fn set_memory_budget(bytes: f64) -> Result<(), JsValue> {
+    if !bytes.is_finite() || bytes < 0.0 {
+        return Err(PasswordError::InvalidInput("Memory budget must be a non-negative number".to_string()).into());
+    }
+
+    let mut budget = MEMORY_BUDGET.lock().unwrap_or_else(|err| err.into_inner());
+    budget.limit = Some(bytes as u64);
+    Ok(())
+}
 
-            let params = Params::new(
-                opts.memory_cost,
-                opts.time_cost,
-                opts.parallelism,
-                None,
-            )?;
+/// Disables the memory budget set by `set_memory_budget`
+#[wasm_bindgen]
+pub fn clear_memory_budget() {
+    let mut budget = MEMORY_BUDGET.lock().unwrap_or_else(|err| err.into_inner());
+    budget.limit = None;
+}
 
-            Argon2::new(
-                argon2::Algorithm::Argon2id,
-                argon2::Version::V0x13,
-                params,
-            )
+/// Verifies a password without ever throwing
+///
+/// A completed verification yields `ok: true` with `valid` set. Malformed
+/// input yields `ok: false`, `valid: false` and the error as
+/// `{ message, code }`. Hashes are accepted in every form `verify` takes.
+#[wasm_bindgen]
+pub fn try_verify(hash: &str, password: &str) -> JsValue {
+    let result = normalize_hash(hash).and_then(|hash| {
+        if hash.is_empty() || password.is_empty() {
+            Err(PasswordError::InvalidInput("Hash and password cannot be empty".to_string()))
+        } else {
+            argon2id_verify_bytes(&hash, password.as_bytes())
         }
-        None => Argon2::default(),
+    });
+
+    let outcome = match result {
+        Ok(valid) => TryVerifyResult { ok: true, valid, error: None },
+        Err(err) => TryVerifyResult {
+            ok: false,
+            valid: false,
+            error: Some(ErrorInfo::from(&err)),
+        },
     };
 
-    argon2
-        .hash_password(password.as_bytes(), &salt)
-        .map(|password_hash| password_hash.to_string())
-        .map_err(|err| PasswordError::InvalidParams(err.to_string()))
+    // Only plain strings and booleans are serialized, so this cannot fail
+    serde_wasm_bindgen::to_value(&outcome).unwrap_or(JsValue::NULL)
 }
 
-/// Internal function to verify a password against a hash
-fn argon2id_verify(options: &VerifyRequest) -> Result<bool, PasswordError> {
-    let password_hash = PasswordHash::new(&options.hash)
-        .map_err(|err| PasswordError::InvalidParams(err.to_string()))?;
+/// Returns true only if both hashes accept the password
+///
+/// Intended for migration test suites that create the same credential two
+/// ways. Both hashes are parsed up front, so a malformed hash always errors.
+#[wasm_bindgen]
+pub fn would_both_accept(hash_a: &str, hash_b: &str, password: &str) -> Result<bool, JsValue> {
+    if hash_a.is_empty() || hash_b.is_empty() || password.is_empty() {
+        return Err(PasswordError::InvalidInput("Hashes and password cannot be empty".to_string()).into());
+    }
 
-    Argon2::default()
-        .verify_password(options.password.as_bytes(), &password_hash)
-        .map(|_| true)
-        .or_else(|err| match err {
-            argon2::password_hash::Error::Password => Ok(false),
-            _ => Err(PasswordError::InvalidParams(err.to_string())),
+    argon2id_would_both_accept(hash_a, hash_b, password.as_bytes()).map_err(|err| {
+        error!("Failed to verify password: {}", err);
+        err.into()
+    })
+}
+
+/// Hashes a password with a server secret and splits the result for separate storage
+///
+/// `public_params` is the PHC string up to and including the salt, and
+/// `verifier_b64` is the keyed tag. Storing them in different places means a
+/// leak of either one, without the secret, is not enough to test guesses.
+#[wasm_bindgen]
+pub fn hash_split_storage(password: &str, secret: &[u8], options: JsValue) -> Result<JsValue, JsValue> {
+    if password.is_empty() {
+        return Err(PasswordError::InvalidInput("Password cannot be empty".to_string()).into());
+    }
+    if secret.is_empty() {
+        return Err(PasswordError::InvalidInput("Secret cannot be empty".to_string()).into());
+    }
+
+    let opts = parse_hash_options(options)?;
+
+    let split = argon2id_keyed_hash(password.as_bytes(), Some(secret), opts)
+        .and_then(|hash| split_verifier(&hash))
+        .map_err(|err| {
+            error!("Failed to hash password: {}", err);
+            JsValue::from(err)
+        })?;
+
+    serde_wasm_bindgen::to_value(&split).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Verifies a password against split-storage parameters and verifier with the server secret
+#[wasm_bindgen]
+pub fn verify_split_storage(
+    public_params: &str,
+    verifier_b64: &str,
+    password: &str,
+    secret: &[u8],
+) -> Result<bool, JsValue> {
+    if public_params.is_empty() || verifier_b64.is_empty() || password.is_empty() {
+        return Err(PasswordError::InvalidInput(
+            "Parameters, verifier and password cannot be empty".to_string(),
+        )
+        .into());
+    }
+
+    let hash = format!("{}${}", public_params, verifier_b64);
+    let password_hash = parse_phc(&hash).map_err(|err| {
+        error!("Failed to verify password: {}", err);
+        JsValue::from(err)
+    })?;
+
+    argon2id_keyed_verify_parsed(&password_hash, password.as_bytes(), Some(secret)).map_err(|err| {
+        error!("Failed to verify password: {}", err);
+        err.into()
+    })
+}
+
+/// Verifies a password against a PHC string whose salt segment is stored elsewhere
+///
+/// `params_and_tag` is the PHC string with the salt segment removed, e.g.
+/// `$argon2id$v=19$m=65536,t=3,p=4$<tag>`, and `salt` is the raw salt bytes.
+#[wasm_bindgen]
+pub fn verify_with_external_salt(params_and_tag: &str, salt: &[u8], password: &str) -> Result<bool, JsValue> {
+    let hash = reinsert_salt(params_and_tag, salt).map_err(|err| {
+        error!("Failed to rebuild hash with external salt: {}", err);
+        JsValue::from(err)
+    })?;
+
+    verify(&hash, password)
+}
+
+/// Recommends parameters for a device from how long a default-parameter hash took on it
+///
+/// Hash time grows roughly linearly with `memory_cost`, so the default memory
+/// cost is scaled to land near a 250 ms target, rounded down to whole MiB and
+/// clamped to 8 MiB..1 GiB. On devices so slow the floor overshoots the
+/// target, `estimated_ms` reports the expected cost at the floor.
+#[wasm_bindgen]
+pub fn min_safe_params(measured_hash_ms_at_default: f64) -> Result<JsValue, JsValue> {
+    let recommendation = argon2id_min_safe_params(measured_hash_ms_at_default).map_err(|err| {
+        error!("Failed to recommend params: {}", err);
+        JsValue::from(err)
+    })?;
+
+    serde_wasm_bindgen::to_value(&recommendation)
+        .map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Hashes a password and records its creation time in the PHC `data` segment
+///
+/// `now_unix` is the current time in Unix seconds. The timestamp is used as
+/// Argon2 associated data, so it is bound into the tag: editing it makes the
+/// hash stop verifying. Plain `verify` works on timestamped hashes unchanged.
+#[wasm_bindgen]
+pub fn hash_timestamped(password: &str, options: JsValue, now_unix: f64) -> Result<String, JsValue> {
+    if password.is_empty() {
+        return Err(PasswordError::InvalidInput("Password cannot be empty".to_string()).into());
+    }
+    if !now_unix.is_finite() || now_unix < 0.0 {
+        return Err(PasswordError::InvalidInput("Timestamp must be a non-negative number".to_string()).into());
+    }
+
+    let opts = parse_hash_options(options)?;
+    let timestamp = (now_unix as u64).to_be_bytes();
+    let salt = generate_salt(opts.as_ref()).map_err(JsValue::from)?;
+
+    argon2id_hash_with_salt(password.as_bytes(), None, Some(&timestamp), &salt, opts).map_err(|err| {
+        error!("Failed to hash password: {}", err);
+        err.into()
+    })
+}
+
+/// Returns the age in days of a hash created by `hash_timestamped`
+#[wasm_bindgen]
+pub fn hash_age_days(hash: &str, now_unix: f64) -> Result<f64, JsValue> {
+    let created = normalize_hash(hash).and_then(|hash| argon2id_timestamp(&hash)).map_err(|err| {
+        error!("Failed to read hash timestamp: {}", err);
+        JsValue::from(err)
+    })?;
+
+    Ok((now_unix - created as f64) / SECONDS_PER_DAY)
+}
+
+/// Verifies a password against a `hash_timestamped` hash and checks its age
+///
+/// `expired` is set when the hash is older than `max_age_days`. It is
+/// reported alongside `valid`, so a correct but expired credential yields
+/// `valid: true, expired: true` and the app can force a refresh.
+#[wasm_bindgen]
+pub fn verify_fresh(hash: &str, password: &str, max_age_days: f64, now_unix: f64) -> Result<JsValue, JsValue> {
+    if !max_age_days.is_finite() || max_age_days < 0.0 {
+        return Err(PasswordError::InvalidInput("Maximum age must be a non-negative number".to_string()).into());
+    }
+
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    let age_days = hash_age_days(&hash, now_unix)?;
+    let result = FreshVerification {
+        valid: verify_normalized(&hash, password)?,
+        expired: age_days > max_age_days,
+    };
+
+    serde_wasm_bindgen::to_value(&result).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Returns a short, salt-independent identifier for a hash's parameter set
+///
+/// The fingerprint is the first 8 bytes of a Blake2b-512 digest over
+/// `algorithm|version|m|t|p`, hex encoded. Hashes that share parameters
+/// share a fingerprint, which makes them easy to group in metrics.
+#[wasm_bindgen]
+pub fn param_fingerprint(hash: &str) -> Result<String, JsValue> {
+    argon2id_param_fingerprint(hash).map_err(|err| {
+        error!("Failed to fingerprint hash params: {}", err);
+        err.into()
+    })
+}
+
+/// Packs a PHC string into a compact binary record
+///
+/// Layout: algorithm byte (0 = argon2id, 1 = argon2i, 2 = argon2d), version
+/// byte (0 if absent), LEB128 varints for m, t and p, then the salt and tag
+/// each prefixed with a varint length. Hashes with `keyid` or `data`
+/// segments cannot be packed.
+#[wasm_bindgen]
+pub fn pack_hash(phc: &str) -> Result<Uint8Array, JsValue> {
+    HashParts::from_phc(phc)
+        .and_then(|parts| parts.pack())
+        .map(|packed| Uint8Array::from(packed.as_slice()))
+        .map_err(|err| {
+            error!("Failed to pack hash: {}", err);
+            err.into()
+        })
+}
+
+/// Restores the PHC string from a record produced by `pack_hash`
+#[wasm_bindgen]
+pub fn unpack_hash(bytes: &[u8]) -> Result<String, JsValue> {
+    HashParts::unpack(bytes)
+        .and_then(|parts| parts.to_phc())
+        .map_err(|err| {
+            error!("Failed to unpack hash: {}", err);
+            err.into()
+        })
+}
+
+/// Verifies a password against a record produced by `pack_hash`
+#[wasm_bindgen]
+pub fn verify_packed(packed: &[u8], password: &str) -> Result<bool, JsValue> {
+    let hash = unpack_hash(packed)?;
+
+    verify(&hash, password)
+}
+
+/// Verifies a password against a PHC string supplied as its UTF-8 bytes
+#[wasm_bindgen]
+pub fn verify_hash_bytes(hash: &[u8], password: &str) -> Result<bool, JsValue> {
+    let hash = std::str::from_utf8(hash).map_err(|err| {
+        error!("Failed to decode hash bytes: {}", err);
+        JsValue::from(PasswordError::InvalidInput(format!("Hash is not valid UTF-8: {}", err)))
+    })?;
+
+    verify(hash, password)
+}
+
+/// Runs known-answer tests to confirm the WASM build computes Argon2id correctly
+///
+/// Hashes a fixed password with a fixed salt and parameters, and verifies a
+/// second stored vector. Returns `true` only if both match the expected
+/// output. Cheap enough to call once at startup.
+#[wasm_bindgen]
+pub fn self_test() -> Result<bool, JsValue> {
+    argon2id_self_test().map_err(|err| {
+        error!("Self-test failed to run: {}", err);
+        err.into()
+    })
+}
+
+/// Verifies a password and reports the time spent in the Argon2 computation
+///
+/// Only the verification itself is timed, not hash parsing. Runtimes that
+/// coarsen timers (such as Cloudflare Workers) may report `0`.
+#[wasm_bindgen]
+pub fn verify_timed(hash: &str, password: &str) -> Result<JsValue, JsValue> {
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    if hash.is_empty() || password.is_empty() {
+        return Err(PasswordError::InvalidInput("Hash and password cannot be empty".to_string()).into());
+    }
+
+    let password_hash = parse_phc(&hash).map_err(|err| {
+        error!("Failed to verify password: {}", err);
+        JsValue::from(err)
+    })?;
+
+    let start = now_ms();
+    let valid = argon2id_verify_parsed(&password_hash, password.as_bytes()).map_err(|err| {
+        error!("Failed to verify password: {}", err);
+        JsValue::from(err)
+    })?;
+    let elapsed_ms = now_ms() - start;
+
+    serde_wasm_bindgen::to_value(&TimedVerification { valid, elapsed_ms })
+        .map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Hashes a password after mapping it through a JavaScript transform
+///
+/// The transform receives the password string and must return a string. It
+/// must be deterministic, and the same transform must be passed to
+/// `verify_with_transform`, or the credential will never verify.
+#[wasm_bindgen]
+pub fn hash_with_transform(
+    password: &str,
+    transform: &js_sys::Function,
+    options: JsValue,
+) -> Result<String, JsValue> {
+    let password = apply_transform(password, transform)?;
+
+    hash(&password, options)
+}
+
+/// Verifies a password after mapping it through the transform used at hash time
+#[wasm_bindgen]
+pub fn verify_with_transform(
+    hash: &str,
+    password: &str,
+    transform: &js_sys::Function,
+) -> Result<bool, JsValue> {
+    let password = apply_transform(password, transform)?;
+
+    verify(hash, &password)
+}
+
+/// Verifies a batch of `{ hash, password }` requests, timing each one
+///
+/// Per-item timings make unusually expensive stored hashes stand out. The
+/// first malformed entry stops the batch with an error naming its index.
+#[wasm_bindgen]
+pub fn verify_batch_timed(requests: JsValue) -> Result<JsValue, JsValue> {
+    let requests: Vec<VerifyRequest> = serde_wasm_bindgen::from_value(requests).map_err(|err| {
+        error!("Failed to deserialize verify requests: {}", err);
+        PasswordError::Serialization(err)
+    })?;
+
+    let batch = argon2id_verify_batch_timed(&requests).map_err(|err| {
+        error!("Failed to verify batch: {}", err);
+        JsValue::from(err)
+    })?;
+
+    serde_wasm_bindgen::to_value(&batch).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Verifies a password but refuses hashes that appear in a denylist of leaked values
+///
+/// A denylisted hash always yields `valid: false` so the caller can force a
+/// reset. The password is still verified so that timing does not reveal
+/// whether the hash was on the list. Both sides are normalized before the
+/// comparison, and malformed denylist entries are skipped.
+#[wasm_bindgen]
+pub fn verify_not_compromised(
+    hash: &str,
+    password: &str,
+    compromised_hashes: Vec<String>,
+) -> Result<JsValue, JsValue> {
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    let matched = verify_normalized(&hash, password)?;
+    let compromised = compromised_hashes
+        .iter()
+        .filter_map(|leaked| normalize_hash(leaked).ok())
+        .any(|leaked| leaked == hash);
+
+    let result = DenylistVerification {
+        valid: matched && !compromised,
+        compromised,
+    };
+
+    serde_wasm_bindgen::to_value(&result).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Verifies a password and reports whether the hash uses an Argon2 version below 0x13
+///
+/// Old-version hashes still verify. With `warn_on_old_version` set, a
+/// successful login against one also logs a warning recommending a rehash.
+#[wasm_bindgen]
+pub fn verify_versioned(hash: &str, password: &str, warn_on_old_version: bool) -> Result<JsValue, JsValue> {
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    let valid = verify_normalized(&hash, password)?;
+
+    let version = parse_phc(&hash).map_err(JsValue::from)?.version;
+    let outdated_version = version.is_some_and(|version| version < argon2::Version::V0x13 as u32);
+
+    if valid && outdated_version && warn_on_old_version {
+        warn!("Verified a hash with outdated Argon2 version; rehash to 0x13 recommended");
+    }
+
+    serde_wasm_bindgen::to_value(&VersionedVerification { valid, outdated_version })
+        .map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Hashes a client-side pre-hashed password (e.g. an HMAC of the password)
+///
+/// The digest is used as the Argon2 password bytes as-is. Only 32 and 64 byte
+/// digests are accepted, so a raw password passed here by mistake is rejected.
+#[wasm_bindgen]
+pub fn hash_prehashed(digest: &[u8], options: JsValue) -> Result<String, JsValue> {
+    validate_digest_len(digest, "Pre-hashed digest")?;
+
+    let opts = parse_hash_options(options)?;
+
+    argon2id_hash(digest, opts).map_err(|err| {
+        error!("Failed to hash pre-hashed password: {}", err);
+        err.into()
+    })
+}
+
+/// Verifies a client-side pre-hashed password against a stored hash
+#[wasm_bindgen]
+pub fn verify_prehashed(hash: &str, digest: &[u8]) -> Result<bool, JsValue> {
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    if hash.is_empty() {
+        return Err(PasswordError::InvalidInput("Hash cannot be empty".to_string()).into());
+    }
+    validate_digest_len(digest, "Pre-hashed digest")?;
+
+    argon2id_verify_bytes(&hash, digest).map_err(|err| {
+        error!("Failed to verify pre-hashed password: {}", err);
+        err.into()
+    })
+}
+
+/// Verifies a key derived by an earlier KDF step, used as the password bytes
+///
+/// For chained setups such as device key to account key: the stored hash
+/// was made with the derived key as its password, e.g. via
+/// `hash_prehashed`. Only 32 and 64 byte keys are accepted, so passing the
+/// user's raw password or a truncated key fails loudly instead of simply
+/// not matching.
+#[wasm_bindgen]
+pub fn verify_derived(hash: &str, derived_key: &[u8]) -> Result<bool, JsValue> {
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    if hash.is_empty() {
+        return Err(PasswordError::InvalidInput("Hash cannot be empty".to_string()).into());
+    }
+    validate_digest_len(derived_key, "Derived key")?;
+
+    argon2id_verify_bytes(&hash, derived_key).map_err(|err| {
+        error!("Failed to verify derived key: {}", err);
+        err.into()
+    })
+}
+
+/// Derives a raw key sized for the named cipher or MAC
+///
+/// Supported algorithms are `"aes256"`, `"chacha20"` and `"hmac_sha256"`.
+/// The key length is chosen from the algorithm so callers never have to
+/// guess byte counts.
+#[wasm_bindgen]
+pub fn derive_key_for(
+    passphrase: &[u8],
+    salt: &[u8],
+    algorithm: &str,
+    options: JsValue,
+) -> Result<Uint8Array, JsValue> {
+    if passphrase.is_empty() {
+        return Err(PasswordError::InvalidInput("Passphrase cannot be empty".to_string()).into());
+    }
+
+    let key_len = key_len_for(algorithm)?;
+    let opts = parse_hash_options(options)?;
+
+    argon2id_derive(passphrase, salt, key_len, opts)
+        .map(|key| Uint8Array::from(key.as_slice()))
+        .map_err(|err| {
+            error!("Failed to derive key: {}", err);
+            err.into()
+        })
+}
+
+/// Reports whether a stored hash can be re-salted without the password
+///
+/// Always `false`: the salt is an input to the hash, so a new salt needs the
+/// plaintext. Re-hash on the user's next successful login instead.
+#[wasm_bindgen]
+pub fn can_resalt() -> bool {
+    false
+}
+
+/// Explains why a hash cannot be re-salted server-side
+///
+/// This always fails with an `InvalidInput` error so the limitation is
+/// discoverable instead of failing silently.
+#[wasm_bindgen]
+pub fn resalt(_hash: &str) -> Result<String, JsValue> {
+    Err(PasswordError::InvalidInput(
+        "cannot re-salt without the plaintext password; re-hash on next login instead".to_string(),
+    )
+    .into())
+}
+
+/// Derives an AES-256-GCM key and returns it as a JWK for `crypto.subtle.importKey`
+///
+/// Returns `{ kty: "oct", k, alg: "A256GCM" }`. The key is always 32 bytes;
+/// an `output_len` other than 32 in `options` is rejected.
+#[wasm_bindgen]
+pub fn derive_key_jwk(passphrase: &[u8], salt: &[u8], options: JsValue) -> Result<JsValue, JsValue> {
+    if passphrase.is_empty() {
+        return Err(PasswordError::InvalidInput("Passphrase cannot be empty".to_string()).into());
+    }
+
+    let opts = parse_hash_options(options)?;
+    if let Some(output_len) = opts.as_ref().and_then(|opts| opts.output_len)
+        && output_len != AES_256_KEY_LEN as u32
+    {
+        return Err(PasswordError::InvalidInput(format!(
+            "A256GCM keys are {} bytes, got output_len {}",
+            AES_256_KEY_LEN, output_len
+        ))
+        .into());
+    }
+
+    let key = argon2id_derive(passphrase, salt, AES_256_KEY_LEN, opts).map_err(|err| {
+        error!("Failed to derive JWK: {}", err);
+        JsValue::from(err)
+    })?;
+    let jwk = Jwk {
+        kty: "oct".to_string(),
+        k: Base64UrlUnpadded::encode_string(&key),
+        alg: "A256GCM".to_string(),
+    };
+
+    serde_wasm_bindgen::to_value(&jwk).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Derives several domain-separated keys from one passphrase
+///
+/// Returns an object mapping each label to a `Uint8Array` of `key_len`
+/// bytes. The label is passed to Argon2 as associated data, so keys for
+/// different labels are independent. Labels must be 1 to 32 bytes long.
+#[wasm_bindgen]
+pub fn derive_keys(
+    passphrase: &[u8],
+    salt: &[u8],
+    labels: Vec<String>,
+    key_len: u32,
+    options: JsValue,
+) -> Result<JsValue, JsValue> {
+    if passphrase.is_empty() {
+        return Err(PasswordError::InvalidInput("Passphrase cannot be empty".to_string()).into());
+    }
+    if labels.iter().any(|label| label.is_empty()) {
+        return Err(PasswordError::InvalidInput("Key labels cannot be empty".to_string()).into());
+    }
+
+    let opts = parse_hash_options(options)?;
+
+    let keys = Object::new();
+    for label in &labels {
+        let key = argon2id_derive_with_data(passphrase, salt, Some(label.as_bytes()), key_len as usize, opts.as_ref())
+            .map_err(|err| {
+                error!("Failed to derive key for label {}: {}", label, err);
+                JsValue::from(err)
+            })?;
+        js_sys::Reflect::set(&keys, &JsValue::from_str(label), &Uint8Array::from(key.as_slice()))
+            .expect("Failed to set derived key");
+    }
+
+    Ok(keys.into())
+}
+
+/// Hashes a password and writes the result onto a caller-provided object
+///
+/// Sets `hash`, `salt` (the PHC base64 salt), `memory_cost`, `time_cost` and
+/// `parallelism` on `out`, so one object can be reused across many hashes.
+#[wasm_bindgen]
+pub fn hash_into_object(password: &str, options: JsValue, out: &Object) -> Result<(), JsValue> {
+    let hash = hash(password, options)?;
+    let password_hash = parse_phc(&hash).map_err(JsValue::from)?;
+    let params = Params::try_from(&password_hash).map_err(|err| JsValue::from(classify_parsed_hash_error(err)))?;
+    let salt = password_hash.salt.map(|salt| salt.to_string()).unwrap_or_default();
+
+    let fields = [
+        ("hash", JsValue::from_str(&hash)),
+        ("salt", JsValue::from_str(&salt)),
+        ("memory_cost", JsValue::from(params.m_cost())),
+        ("time_cost", JsValue::from(params.t_cost())),
+        ("parallelism", JsValue::from(params.p_cost())),
+    ];
+    for (name, value) in &fields {
+        js_sys::Reflect::set(out, &JsValue::from_str(name), value)?;
+    }
+
+    Ok(())
+}
+
+/// Derives a raw Argon2 tag to store as an offline password verifier
+///
+/// The caller keeps the salt and options alongside the verifier; no PHC
+/// string is produced. The tag is `output_len` bytes, 32 by default.
+#[wasm_bindgen]
+pub fn make_verifier(password: &str, salt: &[u8], options: JsValue) -> Result<Uint8Array, JsValue> {
+    if password.is_empty() {
+        return Err(PasswordError::InvalidInput("Password cannot be empty".to_string()).into());
+    }
+
+    let opts = parse_hash_options(options)?;
+    let len = opts
+        .as_ref()
+        .and_then(|opts| opts.output_len)
+        .map_or(Params::DEFAULT_OUTPUT_LEN, |len| len as usize);
+
+    argon2id_derive(password.as_bytes(), salt, len, opts)
+        .map(|tag| Uint8Array::from(tag.as_slice()))
+        .map_err(|err| {
+            error!("Failed to make verifier: {}", err);
+            err.into()
+        })
+}
+
+/// Checks a password against a verifier from `make_verifier` in constant time
+#[wasm_bindgen]
+pub fn check_verifier(verifier: &[u8], password: &str, salt: &[u8], options: JsValue) -> Result<bool, JsValue> {
+    if verifier.is_empty() || password.is_empty() {
+        return Err(PasswordError::InvalidInput("Verifier and password cannot be empty".to_string()).into());
+    }
+
+    let opts = parse_hash_options(options)?;
+
+    argon2id_check_verifier(verifier, password.as_bytes(), salt, opts).map_err(|err| {
+        error!("Failed to check verifier: {}", err);
+        err.into()
+    })
+}
+
+/// Computes a deterministic blind index for a value, keyed by a namespace key
+///
+/// For privacy-preserving equality lookups such as blind email indexing,
+/// not for passwords. The namespace key is the Argon2 secret and the salt is
+/// derived from it, so one namespace always maps a value to the same index
+/// while indices from different keys are unrelated. The index is
+/// `output_len` bytes, 32 by default.
+#[wasm_bindgen]
+pub fn blind_index(value: &[u8], namespace_key: &[u8], options: JsValue) -> Result<Uint8Array, JsValue> {
+    if namespace_key.is_empty() {
+        return Err(PasswordError::InvalidInput("Namespace key cannot be empty".to_string()).into());
+    }
+
+    let opts = parse_hash_options(options)?;
+
+    argon2id_blind_index(value, namespace_key, opts.as_ref())
+        .map(|index| Uint8Array::from(index.as_slice()))
+        .map_err(|err| {
+            error!("Failed to compute blind index: {}", err);
+            err.into()
+        })
+}
+
+/// Compares the memory and time cost of two hashes to detect parameter downgrades
+///
+/// The deltas are `new - old`. Overall strength is judged by `m * t`, the
+/// total number of block operations performed.
+#[wasm_bindgen]
+pub fn compare_strength(old_hash: &str, new_hash: &str) -> Result<JsValue, JsValue> {
+    let comparison = argon2id_compare_strength(old_hash, new_hash).map_err(|err| {
+        error!("Failed to compare hash strength: {}", err);
+        JsValue::from(err)
+    })?;
+
+    serde_wasm_bindgen::to_value(&comparison)
+        .map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Generates deterministic test vectors using fixed salts and parameter sets
+///
+/// Wrapping libraries can pin the returned `{ password, options, hash }`
+/// objects in their own suites.
+#[cfg(feature = "test-vectors")]
+#[wasm_bindgen]
+pub fn generate_vectors() -> Result<JsValue, JsValue> {
+    let vectors = argon2id_generate_vectors().map_err(|err| {
+        error!("Failed to generate test vectors: {}", err);
+        JsValue::from(err)
+    })?;
+
+    serde_wasm_bindgen::to_value(&vectors).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Hashes a password with a salt drawn from a ChaCha20 RNG seeded with `seed`
+///
+/// The same seed always reproduces the same hash, while different seeds
+/// still cover a wide range of salts. For fuzz and property tests only;
+/// never use it for real credentials.
+#[cfg(feature = "fuzz")]
+#[wasm_bindgen]
+pub fn hash_with_seed(password: &str, seed: u64, options: JsValue) -> Result<String, JsValue> {
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    let opts = parse_hash_options(options)?;
+
+    if password.is_empty() && !opts.as_ref().is_some_and(|opts| opts.allow_empty) {
+        return Err(PasswordError::InvalidInput("Password cannot be empty".to_string()).into());
+    }
+
+    generate_salt_with(&mut ChaCha20Rng::seed_from_u64(seed), opts.as_ref())
+        .and_then(|salt| argon2id_hash_with_salt(password.as_bytes(), None, None, &salt, opts))
+        .map_err(|err| {
+            error!("Failed to hash password with seed: {}", err);
+            err.into()
+        })
+}
+
+/// Hashes every password in a batch with the same caller-provided salt
+///
+/// **Not for password storage.** A shared salt means identical passwords
+/// produce identical hashes, which exposes duplicates and enables
+/// precomputation across the whole batch. Use this only for deterministic,
+/// non-authentication indexing where that trade-off is intended.
+#[wasm_bindgen]
+pub fn hash_batch_fixed_salt(passwords: Vec<String>, salt: &[u8], options: JsValue) -> Result<Vec<String>, JsValue> {
+    let opts = parse_hash_options(options)?;
+
+    argon2id_hash_batch_fixed_salt(&passwords, salt, opts).map_err(|err| {
+        error!("Failed to hash batch with fixed salt: {}", err);
+        err.into()
+    })
+}
+
+/// Hashes a value with a salt derived deterministically from `namespace || value`
+///
+/// For pseudonymous lookup tokens, not password storage: the same value in
+/// the same namespace always yields the same hash, so stored results can be
+/// matched without a separate index. The salt is Blake2b-512 of the
+/// concatenation, truncated to `salt_len`. Use fixed namespaces, since the
+/// plain concatenation does not separate the namespace from the value.
+#[wasm_bindgen]
+pub fn hash_namespaced(value: &[u8], namespace: &[u8], options: JsValue) -> Result<String, JsValue> {
+    let opts = parse_hash_options(options)?;
+
+    argon2id_hash_namespaced(value, namespace, opts).map_err(|err| {
+        error!("Failed to hash namespaced value: {}", err);
+        err.into()
+    })
+}
+
+/// Audits a set of stored hashes for mixed algorithms or versions
+///
+/// A set that mixes either one usually means a migration was left half
+/// finished. Unparseable entries are counted in `malformed_count` and do not
+/// affect `homogeneous`.
+#[wasm_bindgen]
+pub fn audit_homogeneity(hashes: Vec<String>) -> Result<JsValue, JsValue> {
+    let audit = argon2id_audit_homogeneity(&hashes);
+
+    serde_wasm_bindgen::to_value(&audit).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Counts stored hashes by `(algorithm, version, m, t, p)`, most common first
+///
+/// Unparseable entries are counted in `malformed` rather than aborting.
+#[wasm_bindgen]
+pub fn param_histogram(hashes: Vec<String>) -> Result<JsValue, JsValue> {
+    let histogram = argon2id_param_histogram(&hashes);
+
+    serde_wasm_bindgen::to_value(&histogram).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Verifies a password, retrying with encoded forms of it that a buggy path may have hashed
+///
+/// Tries the raw password first, then each listed transform in order:
+/// `"url"` (percent-encoding as done by `encodeURIComponent`), `"base64"`
+/// (standard, padded), `"trim"` (surrounding whitespace removed) and
+/// `"nfc"` (Unicode NFC normalization). Each attempt costs a full hash, so
+/// list only the transforms a migration actually needs.
+#[wasm_bindgen]
+pub fn verify_encodings(hash: &str, password: &str, encodings: Vec<String>) -> Result<JsValue, JsValue> {
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    if hash.is_empty() || password.is_empty() {
+        return Err(PasswordError::InvalidInput("Hash and password cannot be empty".to_string()).into());
+    }
+
+    let result = argon2id_verify_encodings(&hash, password, &encodings).map_err(|err| {
+        error!("Failed to verify password encodings: {}", err);
+        JsValue::from(err)
+    })?;
+
+    serde_wasm_bindgen::to_value(&result).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Verifies a password and reports a compute-cost figure for metering
+///
+/// `compute_units` is `m * t / 1024`, i.e. MiB of memory filled times the
+/// number of passes. It is a proxy for the work done, not a wall-clock time,
+/// so it is stable across devices.
+#[wasm_bindgen]
+pub fn verify_metered(hash: &str, password: &str) -> Result<JsValue, JsValue> {
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    let valid = verify_normalized(&hash, password)?;
+
+    let compute_units = argon2id_compute_units(&argon2id_params(&hash).map_err(JsValue::from)?);
+
+    let result = MeteredVerification { valid, compute_units };
+
+    serde_wasm_bindgen::to_value(&result).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Estimates the work a hash costs as a single "N bits" figure
+///
+/// Returns `log2(m * t)`: each pass fills `m` 1 KiB blocks, so this is the
+/// number of block computations per guess. Parallelism only spreads the
+/// same work across lanes and is ignored. Doubling either `m` or `t` adds
+/// one bit.
+#[wasm_bindgen]
+pub fn work_bits(hash: &str) -> Result<f64, JsValue> {
+    argon2id_params(hash).map(|params| argon2id_work_bits(&params)).map_err(|err| {
+        error!("Failed to read hash params: {}", err);
+        err.into()
+    })
+}
+
+/// Splits the cost of a param set into its memory and time contributions
+///
+/// The components are in the same bits as `work_bits` and sum to it. A
+/// `relative_memory_vs_time` near 1 means the profile gets its cost from
+/// memory, which is what makes Argon2 expensive on GPUs and ASICs; lower
+/// values lean on passes. Computed from the params alone, not measured.
+#[wasm_bindgen]
+pub fn cost_breakdown(options: JsValue) -> Result<JsValue, JsValue> {
+    let opts = parse_hash_options(options)?.unwrap_or_default();
+    let params = Params::new(opts.memory_cost, opts.time_cost, opts.parallelism, None).map_err(|err| {
+        error!("Failed to compute cost breakdown: {}", err);
+        JsValue::from(PasswordError::from(err))
+    })?;
+
+    let memory_component = f64::from(params.m_cost()).log2();
+    let time_component = f64::from(params.t_cost()).log2();
+    let breakdown = CostBreakdown {
+        memory_component,
+        time_component,
+        parallelism_divisor: params.p_cost(),
+        relative_memory_vs_time: memory_component / (memory_component + time_component),
+    };
+
+    serde_wasm_bindgen::to_value(&breakdown).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Grows linear memory ahead of time so the first hash avoids the growth cost
+///
+/// Allocates and frees a buffer the size of the Argon2 memory block for the
+/// given `memory_cost`. WebAssembly memory never shrinks, so the pages stay
+/// resident for later hashes: this trades resident memory for latency.
+#[wasm_bindgen]
+pub fn prewarm(options: JsValue) -> Result<(), JsValue> {
+    let opts = parse_hash_options(options)?;
+
+    argon2id_prewarm(opts).map_err(|err| {
+        error!("Failed to prewarm memory: {}", err);
+        err.into()
+    })
+}
+
+/// Returns the minimum and maximum accepted for each hashing option
+#[wasm_bindgen]
+pub fn param_bounds() -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(&argon2id_param_bounds()).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Returns a JSON Schema document describing `HashOptions` and its enforced bounds
+///
+/// The numeric limits come from `param_bounds`, so client-side validation
+/// stays in step with this build. Cross-field rules, such as `memory_cost`
+/// being at least `8 * parallelism`, cannot be expressed and are still only
+/// checked when hashing.
+#[wasm_bindgen]
+pub fn options_json_schema() -> String {
+    let bounds = argon2id_param_bounds();
+    let range = |bounds: &Bounds| serde_json::json!({ "type": "integer", "minimum": bounds.min, "maximum": bounds.max });
+    let optional_range = |bounds: &Bounds| {
+        serde_json::json!({ "type": ["integer", "null"], "minimum": bounds.min, "maximum": bounds.max })
+    };
+
+    let schema = serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "HashOptions",
+        "type": "object",
+        "required": ["time_cost", "memory_cost", "parallelism"],
+        "properties": {
+            "time_cost": range(&bounds.time_cost),
+            "memory_cost": range(&bounds.memory_cost),
+            "parallelism": { "type": "integer", "maximum": bounds.parallelism.max },
+            "pad_password_to": { "type": ["integer", "null"], "minimum": 1, "maximum": MAX_PAD_BUCKET },
+            "algorithm": { "enum": ["argon2id", "argon2i", "argon2d", 0, 1, 2, null] },
+            "allow_empty": { "type": "boolean" },
+            "salt_len": optional_range(&bounds.salt_len),
+            "context": { "type": ["string", "null"] },
+            "output_len": optional_range(&bounds.output_len),
+            "parallelism_auto": { "type": "boolean" },
+            "epoch": { "type": ["integer", "null"], "minimum": 0, "maximum": u32::MAX }
+        },
+        // parallelism may only be 0 when parallelism_auto picks the lane count
+        "if": {
+            "properties": { "parallelism_auto": { "const": true } },
+            "required": ["parallelism_auto"]
+        },
+        "then": { "properties": { "parallelism": { "minimum": 0 } } },
+        "else": { "properties": { "parallelism": { "minimum": bounds.parallelism.min } } }
+    });
+
+    schema.to_string()
+}
+
+/// Internal function to collect the param limits enforced by this build
+fn argon2id_param_bounds() -> ParamBounds {
+    ParamBounds {
+        memory_cost: Bounds {
+            min: Params::MIN_M_COST,
+            max: Params::MAX_M_COST,
+        },
+        time_cost: Bounds {
+            min: Params::MIN_T_COST,
+            max: Params::MAX_T_COST,
+        },
+        parallelism: Bounds {
+            min: Params::MIN_P_COST,
+            max: Params::MAX_P_COST,
+        },
+        salt_len: Bounds {
+            min: argon2::MIN_SALT_LEN as u32,
+            max: MAX_SALT_LEN as u32,
+        },
+        output_len: Bounds {
+            min: Output::MIN_LENGTH as u32,
+            max: Output::MAX_LENGTH as u32,
+        },
+    }
+}
+
+/// Verifies a password and reports whether the hash falls below a parameter floor
+///
+/// `valid` is reported even when `below_policy` is set, so the caller can
+/// tell a correct password on a weak hash (force a reset) from a wrong one.
+#[wasm_bindgen]
+pub fn verify_min_strength(
+    hash: &str,
+    password: &str,
+    min_memory_cost: u32,
+    min_time_cost: u32,
+) -> Result<JsValue, JsValue> {
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    let valid = verify_normalized(&hash, password)?;
+
+    let params = argon2id_params(&hash).map_err(JsValue::from)?;
+    let below_policy = params.m_cost() < min_memory_cost || params.t_cost() < min_time_cost;
+
+    let result = PolicyVerification { valid, below_policy };
+
+    serde_wasm_bindgen::to_value(&result).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Computes an HMAC-SHA256 tag over `data` keyed by an Argon2id-derived key
+#[wasm_bindgen]
+pub fn mac(data: &[u8], passphrase: &[u8], salt: &[u8], options: JsValue) -> Result<Uint8Array, JsValue> {
+    if passphrase.is_empty() {
+        return Err(PasswordError::InvalidInput("Passphrase cannot be empty".to_string()).into());
+    }
+
+    let opts = parse_hash_options(options)?;
+
+    argon2id_mac(data, passphrase, salt, opts)
+        .map(|mac| Uint8Array::from(mac.finalize().into_bytes().as_slice()))
+        .map_err(|err| {
+            error!("Failed to compute MAC: {}", err);
+            err.into()
+        })
+}
+
+/// Checks a tag produced by `mac` in constant time
+#[wasm_bindgen]
+pub fn mac_verify(
+    data: &[u8],
+    tag: &[u8],
+    passphrase: &[u8],
+    salt: &[u8],
+    options: JsValue,
+) -> Result<bool, JsValue> {
+    if passphrase.is_empty() {
+        return Err(PasswordError::InvalidInput("Passphrase cannot be empty".to_string()).into());
+    }
+
+    let opts = parse_hash_options(options)?;
+
+    argon2id_mac(data, passphrase, salt, opts)
+        .map(|mac| mac.verify_slice(tag).is_ok())
+        .map_err(|err| {
+            error!("Failed to verify MAC: {}", err);
+            err.into()
+        })
+}
+
+/// Parses the reference CLI's compact `m=65536,t=3,p=4` form into hash options
+///
+/// Keys may appear in any order; omitted keys take their defaults.
+#[wasm_bindgen]
+pub fn parse_param_string(s: &str) -> Result<JsValue, JsValue> {
+    let options = argon2id_parse_param_string(s).map_err(|err| {
+        error!("Failed to parse param string: {}", err);
+        JsValue::from(err)
+    })?;
+
+    serde_wasm_bindgen::to_value(&options).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Formats hash options as the compact `m=...,t=...,p=...` form
+#[wasm_bindgen]
+pub fn format_param_string(options: JsValue) -> Result<String, JsValue> {
+    let opts = parse_hash_options(options)?.unwrap_or_default();
+
+    Ok(format!("m={},t={},p={}", opts.memory_cost, opts.time_cost, opts.parallelism))
+}
+
+/// Packs `m`, `t` and `p` into one integer-valued number for URLs or QR codes
+///
+/// Layout, most significant first, 53 bits so the value is exact as a JS
+/// number: `m` in bits 21..53 (32 bits), `t` in bits 8..21 (13 bits, at most
+/// 8191) and `p` in bits 0..8 (at most 255). Other options are not packed.
+#[wasm_bindgen]
+pub fn pack_params(options: JsValue) -> Result<f64, JsValue> {
+    let opts = parse_hash_options(options)?.unwrap_or_default();
+
+    argon2id_pack_params(&opts).map_err(|err| {
+        error!("Failed to pack params: {}", err);
+        err.into()
+    })
+}
+
+/// Unpacks a number produced by `pack_params` into hash options
+#[wasm_bindgen]
+pub fn unpack_params(packed: f64) -> Result<JsValue, JsValue> {
+    let options = argon2id_unpack_params(packed).map_err(|err| {
+        error!("Failed to unpack params: {}", err);
+        JsValue::from(err)
+    })?;
+
+    serde_wasm_bindgen::to_value(&options).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Verifies a legacy `base64(salt)$base64(tag)` credential against shared params
+///
+/// The migrated-from system kept Argon2 params in config rather than in each
+/// credential, so they must be passed as `options`. Padded and unpadded
+/// standard base64 are both accepted.
+#[wasm_bindgen]
+pub fn verify_legacy_concat(stored: &str, password: &[u8], options: JsValue) -> Result<bool, JsValue> {
+    if stored.is_empty() || password.is_empty() {
+        return Err(PasswordError::InvalidInput("Hash and password cannot be empty".to_string()).into());
+    }
+
+    let opts = parse_hash_options(options)?;
+
+    argon2id_verify_legacy_concat(stored, password, opts).map_err(|err| {
+        error!("Failed to verify legacy credential: {}", err);
+        err.into()
+    })
+}
+
+/// Hashes `pepper || password` with plain, non-keyed Argon2
+///
+/// For migrating credentials from systems that peppered by concatenation
+/// rather than through Argon2's secret input. New credentials should prefer
+/// the keyed functions.
+#[wasm_bindgen]
+pub fn hash_prepended_pepper(password: &str, pepper: &[u8], options: JsValue) -> Result<String, JsValue> {
+    if password.is_empty() {
+        return Err(PasswordError::InvalidInput("Password cannot be empty".to_string()).into());
+    }
+
+    let opts = parse_hash_options(options)?;
+
+    prepend_pepper(pepper, password.as_bytes())
+        .and_then(|input| argon2id_hash(&input, opts))
+        .map_err(|err| {
+            error!("Failed to hash peppered password: {}", err);
+            err.into()
+        })
+}
+
+/// Verifies a password against a hash produced by `hash_prepended_pepper`
+#[wasm_bindgen]
+pub fn verify_prepended_pepper(hash: &str, password: &str, pepper: &[u8]) -> Result<bool, JsValue> {
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    if hash.is_empty() || password.is_empty() {
+        return Err(PasswordError::InvalidInput("Hash and password cannot be empty".to_string()).into());
+    }
+
+    prepend_pepper(pepper, password.as_bytes())
+        .and_then(|input| argon2id_verify_bytes(&hash, &input))
+        .map_err(|err| {
+            error!("Failed to verify peppered password: {}", err);
+            err.into()
+        })
+}
+
+/// Reports whether the runtime's CSPRNG (`crypto.getRandomValues`) is usable
+///
+/// Draws a few bytes from the OS RNG and returns `false` on failure instead
+/// of throwing, so callers can check before hashing with a random salt.
+#[wasm_bindgen]
+pub fn has_secure_rng() -> bool {
+    let mut probe = [0u8; 16];
+    OsRng.try_fill_bytes(&mut probe).is_ok()
+}
+
+/// Verifies a password, tolerating non-standard base64 in the salt segment
+///
+/// Some encoders emit URL-safe or padded base64 salts, which strict PHC
+/// parsing rejects. When standard verification fails, the salt is re-encoded
+/// and verification retried. Only the encoding is relaxed, never the params.
+#[wasm_bindgen]
+pub fn verify_lenient(hash: &str, password: &str) -> Result<bool, JsValue> {
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    if hash.is_empty() || password.is_empty() {
+        return Err(PasswordError::InvalidInput("Hash and password cannot be empty".to_string()).into());
+    }
+
+    argon2id_verify_lenient(&hash, password.as_bytes()).map_err(|err| {
+        error!("Failed to verify password: {}", err);
+        err.into()
+    })
+}
+
+/// Verifies a password with some of the hash's `m`, `t` or `p` replaced
+///
+/// Rescues legacy hashes whose encoded params do not match the params the
+/// tag was computed with. Algorithm, version, salt and any other params
+/// still come from the PHC string. Only use this for hashes known to be
+/// affected; it accepts any params the caller supplies.
+#[wasm_bindgen]
+pub fn verify_with_param_override(hash: &str, password: &str, param_override: JsValue) -> Result<bool, JsValue> {
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    if hash.is_empty() || password.is_empty() {
+        return Err(PasswordError::InvalidInput("Hash and password cannot be empty".to_string()).into());
+    }
+
+    let param_override: Option<ParamOverride> = serde_wasm_bindgen::from_value(param_override).map_err(|err| {
+        error!("Failed to deserialize param override: {}", err);
+        PasswordError::Serialization(err)
+    })?;
+
+    argon2id_verify_with_param_override(&hash, password.as_bytes(), &param_override.unwrap_or_default()).map_err(|err| {
+        error!("Failed to verify password with param override: {}", err);
+        err.into()
+    })
+}
+
+/// Heuristically reports whether a hash looks like one this crate produced with defaults
+///
+/// True when the string parses as `argon2id` at version 19 with a tag of
+/// the default 32 bytes. Other libraries often share these defaults, and
+/// hashes this crate made with a custom algorithm or `output_len` return
+/// false, so treat the answer as a triage hint only. Never errors.
+#[wasm_bindgen]
+pub fn is_native_hash(hash: &str) -> bool {
+    parse_phc(hash.trim_ascii()).is_ok_and(|parsed| {
+        parsed.algorithm == argon2::Algorithm::Argon2id.ident()
+            && parsed.version == Some(argon2::Version::V0x13 as u32)
+            && parsed.hash.is_some_and(|tag| tag.len() == Params::DEFAULT_OUTPUT_LEN)
+    })
+}
+
+/// Compares two PHC strings semantically rather than textually
+///
+/// Algorithm, version, params, salt bytes and tag bytes must all match;
+/// parameter order and other encoding details are ignored. The tags are
+/// compared in constant time.
+#[wasm_bindgen]
+pub fn hashes_equivalent(a: &str, b: &str) -> Result<bool, JsValue> {
+    argon2id_hashes_equivalent(a, b).map_err(|err| {
+        error!("Failed to compare hashes: {}", err);
+        err.into()
+    })
+}
+
+/// Hashes a password into a hex-encoded `$argon2id-hex$...` string
+///
+/// Same layout as a PHC string, but the salt and tag are lowercase hex so
+/// the result contains no base64. This format is specific to this crate and
+/// does not interoperate with other Argon2 libraries; read it with
+/// `verify` or `verify_hex`. The layout has no keyid or data segment, so
+/// options that set associated data, such as `epoch`, are rejected.
+#[wasm_bindgen]
+pub fn hash_hex(password: &str, options: JsValue) -> Result<String, JsValue> {
+    let phc = hash(password, options)?;
+
+    HashParts::from_phc(&phc).and_then(|parts| parts.to_hex_format()).map_err(|err| {
+        error!("Failed to encode hex hash: {}", err);
+        err.into()
+    })
+}
+
+/// Verifies a password against a hash produced by `hash_hex`
+#[wasm_bindgen]
+pub fn verify_hex(hash: &str, password: &str) -> Result<bool, JsValue> {
+    let hash = unwrap_envelope(hash.trim_ascii()).map_err(JsValue::from)?;
+    if hash.is_empty() || password.is_empty() {
+        return Err(PasswordError::InvalidInput("Hash and password cannot be empty".to_string()).into());
+    }
+
+    HashParts::from_hex_format(hash)
+        .and_then(|parts| parts.to_phc())
+        .and_then(|phc| argon2id_verify_bytes(&phc, password.as_bytes()))
+        .map_err(|err| {
+            error!("Failed to verify hex hash: {}", err);
+            err.into()
+        })
+}
+
+/// Registers a named parameter preset, replacing any preset with the same name
+#[wasm_bindgen]
+pub fn register_preset(name: &str, options: JsValue) -> Result<(), JsValue> {
+    if name.is_empty() {
+        return Err(PasswordError::InvalidInput("Preset name cannot be empty".to_string()).into());
+    }
+
+    let opts = parse_hash_options(options)?.unwrap_or_default();
+    argon2id_keyed_instance(Some(&opts), None, None).map_err(|err| {
+        error!("Failed to register preset: {}", err);
+        JsValue::from(err)
+    })?;
+
+    let mut presets = PRESETS.lock().unwrap_or_else(|err| err.into_inner());
+    match presets.iter_mut().find(|(existing, _)| existing == name) {
+        Some((_, preset)) => *preset = opts,
+        None => presets.push((name.to_string(), opts)),
+    }
+    Ok(())
+}
+
+/// Returns the name of the registered preset whose params match the hash, or `null`
+///
+/// A hash matching no preset was created with params that are no longer in
+/// use, so a `null` result is the signal to rehash.
+#[wasm_bindgen]
+pub fn needs_rehash_against_presets(hash: &str) -> Result<JsValue, JsValue> {
+    let parts = HashParts::from_phc(hash).map_err(|err| {
+        error!("Failed to parse hash: {}", err);
+        JsValue::from(err)
+    })?;
+
+    let presets = PRESETS.lock().unwrap_or_else(|err| err.into_inner());
+    let matched = presets.iter().find(|(_, opts)| {
+        argon2::Algorithm::from(opts.algorithm.unwrap_or(Algorithm::Argon2id)) == parts.algorithm
+            && opts.memory_cost == parts.params.m_cost()
+            && opts.time_cost == parts.params.t_cost()
+            && opts.parallelism == parts.params.p_cost()
+    });
+
+    Ok(matched.map_or(JsValue::NULL, |(name, _)| JsValue::from_str(name)))
+}
+
+/// Verifies a password, reusing a cached result for the same hash and password
+///
+/// Results are cached in memory for `ttl_ms` after `now_ms`, so repeat
+/// checks of one credential skip the Argon2 work. Only a MAC of the hash
+/// and password under a random per-process key is stored, and entries never
+/// match a different password. Errors are not cached.
+#[wasm_bindgen]
+pub fn verify_cached(hash: &str, password: &str, ttl_ms: f64, now_ms: f64) -> Result<bool, JsValue> {
+    if !ttl_ms.is_finite() || ttl_ms < 0.0 || !now_ms.is_finite() {
+        return Err(PasswordError::InvalidInput("TTL and timestamp must be finite, TTL non-negative".to_string()).into());
+    }
+
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    let key = verify_cache_key(&hash, password).map_err(JsValue::from)?;
+    {
+        let mut cache = VERIFY_CACHE.lock().unwrap_or_else(|err| err.into_inner());
+        cache.retain(|_, entry| entry.expires_at > now_ms);
+        if let Some(entry) = cache.get(&key) {
+            return Ok(entry.valid);
+        }
+    }
+
+    // The lock is not held while verifying, so other calls are not blocked
+    let valid = verify_normalized(&hash, password)?;
+
+    let mut cache = VERIFY_CACHE.lock().unwrap_or_else(|err| err.into_inner());
+    if cache.len() >= MAX_VERIFY_CACHE_ENTRIES
+        && let Some(soonest) = cache
+            .iter()
+            .min_by(|a, b| a.1.expires_at.total_cmp(&b.1.expires_at))
+            .map(|(key, _)| *key)
+    {
+        cache.remove(&soonest);
+    }
+    cache.insert(
+        key,
+        CachedVerification {
+            valid,
+            expires_at: now_ms + ttl_ms,
+        },
+    );
+
+    Ok(valid)
+}
+
+/// Returns the exact bytes Argon2 receives for a password after preprocessing
+///
+/// Applies the same preprocessing as `hash` (`pad_password_to`, then
+/// `context`) without hashing, to help debug cross-platform verification mismatches.
+#[wasm_bindgen]
+pub fn effective_password(password: &str, options: JsValue) -> Result<Uint8Array, JsValue> {
+    let opts = parse_hash_options(options)?;
+
+    let opts = opts.unwrap_or_default();
+
+    preprocess_password(password.as_bytes(), opts.pad_password_to, opts.context.as_deref())
+        .map(|bytes| Uint8Array::from(bytes.as_slice()))
+        .map_err(|err| {
+            error!("Failed to preprocess password: {}", err);
+            err.into()
+        })
+}
+
+/// Hashes a password and appends a MAC over the algorithm, version and params
+///
+/// The result is `<phc>$<mac>`, where `mac` is an unpadded base64
+/// HMAC-SHA256 keyed with `secret`. It only verifies with
+/// `verify_authenticated_params`, which refuses hashes whose parameters were
+/// edited (e.g. an attacker lowering `m=` to cheapen cracking).
+#[wasm_bindgen]
+pub fn hash_authenticated_params(password: &str, secret: &[u8], options: JsValue) -> Result<String, JsValue> {
+    if secret.is_empty() {
+        return Err(PasswordError::InvalidInput("Secret cannot be empty".to_string()).into());
+    }
+
+    let phc = hash(password, options)?;
+
+    params_mac(&phc, secret)
+        .map(|mac| format!("{}${}", phc, Base64Unpadded::encode_string(&mac.finalize().into_bytes())))
+        .map_err(|err| {
+            error!("Failed to authenticate hash params: {}", err);
+            err.into()
+        })
+}
+
+/// Verifies a hash from `hash_authenticated_params`, checking its params MAC first
+///
+/// A MAC mismatch is an error rather than `false`, since it means the stored
+/// hash was tampered with, not that the password was wrong.
+#[wasm_bindgen]
+pub fn verify_authenticated_params(hash: &str, password: &str, secret: &[u8]) -> Result<bool, JsValue> {
+    if secret.is_empty() {
+        return Err(PasswordError::InvalidInput("Secret cannot be empty".to_string()).into());
+    }
+
+    let hash = unwrap_envelope(hash.trim_ascii()).map_err(JsValue::from)?;
+    let (phc, mac) = hash
+        .rsplit_once('$')
+        .ok_or_else(|| JsValue::from(PasswordError::InvalidInput("Hash has no params MAC".to_string())))?;
+    let tag = Base64Unpadded::decode_vec(mac)
+        .map_err(|_| JsValue::from(PasswordError::InvalidInput("Params MAC is not valid base64".to_string())))?;
+
+    let authentic = params_mac(phc, secret).map(|mac| mac.verify_slice(&tag).is_ok())?;
+    if !authentic {
+        warn!("Rejected hash whose params failed authentication");
+        return Err(PasswordError::InvalidInput("Hash params failed authentication".to_string()).into());
+    }
+
+    verify(phc, password)
+}
+
+/// Verifies a password read in chunks from a byte source callback
+///
+/// `next_chunk` is called repeatedly and must return a `Uint8Array`; an empty
+/// array ends the stream. Chunks are accumulated in a buffer that is zeroed
+/// when dropped, so the password never exists as a JS string.
+#[wasm_bindgen]
+pub fn verify_streaming(next_chunk: js_sys::Function, hash: &str) -> Result<bool, JsValue> {
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    if hash.is_empty() {
+        return Err(PasswordError::InvalidInput("Hash cannot be empty".to_string()).into());
+    }
+
+    read_password_stream(&next_chunk)
+        .and_then(|password| {
+            if password.is_empty() {
+                return Err(PasswordError::InvalidInput("Password cannot be empty".to_string()));
+            }
+            argon2id_verify_bytes(&hash, &password)
+        })
+        .map_err(|err| {
+            error!("Failed to verify streamed password: {}", err);
+            err.into()
+        })
+}
+
+/// Reports which PHC fields are present in a hash
+///
+/// Useful for diagnosing third-party hashes, e.g. one that fails because it
+/// has no version field.
+#[wasm_bindgen]
+pub fn hash_field_presence(hash: &str) -> Result<JsValue, JsValue> {
+    let parsed = parse_phc(hash).map_err(|err| {
+        error!("Failed to parse hash: {}", err);
+        JsValue::from(err)
+    })?;
+
+    let presence = FieldPresence {
+        has_version: parsed.version.is_some(),
+        has_keyid: parsed.params.get("keyid").is_some(),
+        has_data: parsed.params.get("data").is_some(),
+        has_salt: parsed.salt.is_some(),
+        has_output: parsed.hash.is_some(),
+    };
+
+    serde_wasm_bindgen::to_value(&presence).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Verifies a password unless the hash's `memory_cost` exceeds a ceiling
+///
+/// The params are checked before anything is allocated, so a hash with a
+/// huge `m=` cannot be used to exhaust memory at verify time. Over-limit
+/// hashes yield `valid: false, rejected: true` with a `reason`.
+#[wasm_bindgen]
+pub fn verify_bounded(hash: &str, password: &str, max_memory_cost: u32) -> Result<JsValue, JsValue> {
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    let params = argon2id_params(&hash).map_err(|err| {
+        error!("Failed to parse hash: {}", err);
+        JsValue::from(err)
+    })?;
+
+    let result = if params.m_cost() > max_memory_cost {
+        warn!("Refused to verify hash with memory_cost {} over limit {}", params.m_cost(), max_memory_cost);
+        BoundedVerification {
+            valid: false,
+            rejected: true,
+            reason: Some("memory_cost exceeds limit".to_string()),
+        }
+    } else {
+        BoundedVerification {
+            valid: verify_normalized(&hash, password)?,
+            rejected: false,
+            reason: None,
+        }
+    };
+
+    serde_wasm_bindgen::to_value(&result).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Wraps a PHC string in a versioned `v1:<phc>` storage envelope
+///
+/// `verify` accepts both wrapped and bare hashes, so stored values can be
+/// migrated to the envelope gradually.
+#[wasm_bindgen]
+pub fn wrap_credential(hash: &str) -> String {
+    format!("{}{}", CREDENTIAL_ENVELOPE_V1, hash)
+}
+
+/// Extracts the PHC string from a `v1:` envelope; bare PHC strings pass through
+#[wasm_bindgen]
+pub fn unwrap_credential(env: &str) -> Result<String, JsValue> {
+    unwrap_envelope(env).map(str::to_string).map_err(|err| {
+        error!("Failed to unwrap credential: {}", err);
+        err.into()
+    })
+}
+
+/// Hashes an ordered list of fields, e.g. username, realm and password
+///
+/// Each field is length-prefixed before concatenation, so `["ab", "c"]` and
+/// `["a", "bc"]` hash differently. Verify with `verify_fields` using the
+/// same fields in the same order.
+#[wasm_bindgen]
+pub fn hash_fields(fields: Vec<String>, options: JsValue) -> Result<String, JsValue> {
+    let opts = parse_hash_options(options)?;
+
+    canonical_fields(&fields)
+        .and_then(|canonical| argon2id_hash(&canonical, opts))
+        .map_err(|err| {
+            error!("Failed to hash fields: {}", err);
+            err.into()
+        })
+}
+
+/// Verifies an ordered list of fields against a hash from `hash_fields`
+#[wasm_bindgen]
+pub fn verify_fields(hash: &str, fields: Vec<String>) -> Result<bool, JsValue> {
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    if hash.is_empty() {
+        return Err(PasswordError::InvalidInput("Hash cannot be empty".to_string()).into());
+    }
+
+    canonical_fields(&fields)
+        .and_then(|canonical| argon2id_verify_bytes(&hash, &canonical))
+        .map_err(|err| {
+            error!("Failed to verify fields: {}", err);
+            err.into()
+        })
+}
+
+/// Assembles a standard PHC string from separately stored salt, tag and params
+///
+/// Intended for migrating raw records. The result verifies with `verify` if
+/// `tag` was the correct Argon2 output for the password under `options`.
+#[wasm_bindgen]
+pub fn build_phc(salt: &[u8], tag: &[u8], options: JsValue) -> Result<String, JsValue> {
+    let opts = parse_hash_options(options)?.unwrap_or_default();
+
+    argon2id_build_phc(salt, tag, &opts).map_err(|err| {
+        error!("Failed to build PHC string: {}", err);
+        err.into()
+    })
+}
+
+/// Clears all module-global state: presets, the verify cache and its key, the memory budget and the event sink
+///
+/// Makes the module deterministic between tests or tenant contexts.
+/// Reservations held by in-flight hashes are left alone so they release
+/// cleanly.
+#[wasm_bindgen]
+pub fn reset_state() {
+    PRESETS.lock().unwrap_or_else(|err| err.into_inner()).clear();
+    VERIFY_CACHE.lock().unwrap_or_else(|err| err.into_inner()).clear();
+    *VERIFY_CACHE_SECRET.lock().unwrap_or_else(|err| err.into_inner()) = None;
+    clear_memory_budget();
+    clear_event_sink();
+}
+
+/// Registers a callback invoked with a `DiagnosticEvent` object for each hash and failed verify
+///
+/// Replaces any previous sink. Exceptions thrown by the callback are
+/// logged and otherwise ignored.
+#[wasm_bindgen]
+pub fn set_event_sink(sink: js_sys::Function) {
+    EVENT_SINK.with(|cell| *cell.borrow_mut() = Some(sink));
+}
+
+/// Removes the callback registered with `set_event_sink`
+#[wasm_bindgen]
+pub fn clear_event_sink() {
+    EVENT_SINK.with(|cell| *cell.borrow_mut() = None);
+}
+
+/// Hashes a password into a fixed-width binary record for columnar storage
+///
+/// Layout: algorithm (1 byte, 0 = argon2id, 1 = argon2i, 2 = argon2d),
+/// version (1), salt length (1), then `m`, `t` and `p` as big-endian u32s,
+/// then the salt and the tag. `salt_len` and `output_len` must both be set
+/// so every record made with the same options has the same width.
+#[wasm_bindgen]
+pub fn hash_fixed_width(password: &str, options: JsValue) -> Result<Uint8Array, JsValue> {
+    let opts = parse_hash_options(options)?;
+    if opts.as_ref().is_none_or(|opts| opts.salt_len.is_none() || opts.output_len.is_none()) {
+        return Err(PasswordError::InvalidInput(
+            "salt_len and output_len must be set for fixed-width hashes".to_string(),
+        )
+        .into());
+    }
+
+    if password.is_empty() && !opts.as_ref().is_some_and(|opts| opts.allow_empty) {
+        return Err(PasswordError::InvalidInput("Password cannot be empty".to_string()).into());
+    }
+
+    argon2id_hash(password.as_bytes(), opts)
+        .and_then(|phc| HashParts::from_phc(&phc))
+        .and_then(|parts| parts.to_fixed_width())
+        .map(|record| Uint8Array::from(record.as_slice()))
+        .map_err(|err| {
+            error!("Failed to encode fixed-width hash: {}", err);
+            err.into()
+        })
+}
+
+/// Verifies a password against a record produced by `hash_fixed_width`
+#[wasm_bindgen]
+pub fn verify_fixed_width(record: &[u8], password: &str) -> Result<bool, JsValue> {
+    if password.is_empty() {
+        return Err(PasswordError::InvalidInput("Password cannot be empty".to_string()).into());
+    }
+
+    HashParts::from_fixed_width(record)
+        .and_then(|parts| parts.to_phc())
+        .and_then(|phc| argon2id_verify_bytes(&phc, password.as_bytes()))
+        .map_err(|err| {
+            error!("Failed to verify fixed-width hash: {}", err);
+            err.into()
+        })
+}
+
+/// Verifies a password and reports the hash's algorithm and version
+///
+/// The algorithm and version are read from the PHC string, so they are
+/// reported even when the password is wrong.
+#[wasm_bindgen]
+pub fn verify_identify(hash: &str, password: &str) -> Result<JsValue, JsValue> {
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    let parts = HashParts::from_phc(&hash).map_err(|err| {
+        error!("Failed to parse hash: {}", err);
+        JsValue::from(err)
+    })?;
+
+    let result = IdentifiedVerification {
+        valid: verify_normalized(&hash, password)?,
+        algorithm: parts.algorithm.as_str().to_string(),
+        // argon2 verifies a hash without a version field as 0x13
+        version: parts.version.unwrap_or(argon2::Version::default() as u32),
+    };
+
+    serde_wasm_bindgen::to_value(&result).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Hashes a password with `time_cost` tuned to take roughly `target_ms` on this device
+///
+/// A single-pass probe with the given memory and parallelism is timed, then
+/// `time_cost` is set to the number of passes that fits the target, clamped
+/// to 1..=16. The chosen params are embedded in the hash, so plain `verify`
+/// works.
+#[wasm_bindgen]
+pub fn hash_adaptive(password: &str, target_ms: f64, options: JsValue) -> Result<JsValue, JsValue> {
+    let opts = parse_hash_options(options)?;
+
+    if password.is_empty() && !opts.as_ref().is_some_and(|opts| opts.allow_empty) {
+        return Err(PasswordError::InvalidInput("Password cannot be empty".to_string()).into());
+    }
+
+    let adaptive = argon2id_hash_adaptive(password.as_bytes(), target_ms, opts.unwrap_or_default()).map_err(|err| {
+        error!("Failed to hash password adaptively: {}", err);
+        JsValue::from(err)
+    })?;
+
+    serde_wasm_bindgen::to_value(&adaptive).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Formats a `memory_cost` in KiB using the largest exact unit, e.g. `"64 MiB"`
+#[wasm_bindgen]
+pub fn format_memory(memory_cost_kib: u32) -> String {
+    let (value, unit) = MEMORY_UNITS
+        .iter()
+        .rev()
+        .find(|(_, kib)| memory_cost_kib != 0 && memory_cost_kib.is_multiple_of(*kib))
+        .map_or((memory_cost_kib, "KiB"), |(unit, kib)| (memory_cost_kib / kib, *unit));
+
+    format!("{} {}", value, unit)
+}
+
+/// Parses a size such as `"64MiB"` or `"1 GiB"` into a `memory_cost` in KiB
+///
+/// Accepts whole numbers with a `KiB`, `MiB` or `GiB` suffix, case-insensitive.
+#[wasm_bindgen]
+pub fn parse_memory(s: &str) -> Result<u32, JsValue> {
+    argon2id_parse_memory(s).map_err(|err| {
+        error!("Failed to parse memory size: {}", err);
+        err.into()
+    })
+}
+
+/// Verifies a password and recommends what the login flow should do next
+///
+/// `action` is `"deny"` for a wrong password, `"reset"` for a correct one on
+/// a hash below the policy floor or past its maximum age, `"rehash"` when the
+/// hash is acceptable but its params differ from `target`, and `"ok"`
+/// otherwise. Hashes without an embedded timestamp are never treated as
+/// expired.
+#[wasm_bindgen]
+pub fn login_check(hash: &str, password: &str, policy: JsValue) -> Result<JsValue, JsValue> {
+    let policy: Option<LoginPolicy> = serde_wasm_bindgen::from_value(policy).map_err(|err| {
+        error!("Failed to deserialize login policy: {}", err);
+        PasswordError::Serialization(err)
+    })?;
+
+    let hash = normalize_hash(hash).map_err(JsValue::from)?;
+    let valid = verify_normalized(&hash, password)?;
+    let action = if valid {
+        argon2id_login_action(&hash, &policy.unwrap_or_default()).map_err(|err| {
+            error!("Failed to apply login policy: {}", err);
+            JsValue::from(err)
+        })?
+    } else {
+        "deny"
+    };
+
+    let decision = LoginDecision {
+        valid,
+        action: action.to_string(),
+    };
+
+    serde_wasm_bindgen::to_value(&decision).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Hashes a password in the exact encoding printed by the reference `argon2` C CLI
+///
+/// The CLI's `Encoded:` line is a standard PHC string with params in
+/// `m,t,p` order and unpadded base64, which is what `hash` already emits, so
+/// this is `hash` under a name that documents the guarantee. The CLI's other
+/// output lines (`Type:`, raw hex `Hash:`, timings) are not reproduced.
+#[wasm_bindgen]
+pub fn hash_reference_format(password: &str, options: JsValue) -> Result<String, JsValue> {
+    hash(password, options)
+}
+
+/// Verifies a password against a hash copied from the reference C CLI's output
+///
+/// Accepts the bare encoded string or the whole `Encoded:` line, with any
+/// surrounding whitespace.
+#[wasm_bindgen]
+pub fn verify_reference_format(hash: &str, password: &str) -> Result<bool, JsValue> {
+    let hash = hash.trim();
+    let hash = hash.strip_prefix("Encoded:").map_or(hash, str::trim_start);
+
+    verify(hash, password)
+}
+
+/// Hashes a password only if it meets a strength policy
+///
+/// Fails with `WeakPassword` listing every unmet rule, without hashing.
+#[wasm_bindgen]
+pub fn hash_with_policy(password: &str, policy: JsValue, options: JsValue) -> Result<String, JsValue> {
+    let policy: Option<PasswordPolicy> = serde_wasm_bindgen::from_value(policy).map_err(|err| {
+        error!("Failed to deserialize password policy: {}", err);
+        PasswordError::Serialization(err)
+    })?;
+
+    check_password_policy(password, &policy.unwrap_or_default()).map_err(JsValue::from)?;
+
+    hash(password, options)
+}
+
+/// Times one hash under each param set to preview the cost of a param bump
+///
+/// A single sample each, so results are noisy for params that hash in a
+/// few milliseconds; use `throughput` for steadier figures.
+#[wasm_bindgen]
+pub fn latency_delta(old_options: JsValue, new_options: JsValue) -> Result<JsValue, JsValue> {
+    let old_options = parse_hash_options(old_options)?.unwrap_or_default();
+    let new_options = parse_hash_options(new_options)?.unwrap_or_default();
+
+    let delta = argon2id_latency_delta(old_options, new_options).map_err(|err| {
+        error!("Failed to measure latency delta: {}", err);
+        JsValue::from(err)
+    })?;
+
+    serde_wasm_bindgen::to_value(&delta).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Estimates how many hashes per second this device computes with `options`
+///
+/// Hashes repeatedly for roughly `duration_ms` of wall-clock time, always
+/// finishing the hash in flight, so the window may overrun by one hash.
+#[wasm_bindgen]
+pub fn throughput(options: JsValue, duration_ms: u32) -> Result<f64, JsValue> {
+    let opts = parse_hash_options(options)?.unwrap_or_default();
+
+    argon2id_throughput(opts, duration_ms).map_err(|err| {
+        error!("Failed to measure throughput: {}", err);
+        err.into()
+    })
+}
+
+/// Verifies a password unless a caller-supplied clock is already past `deadline_ms`
+///
+/// Returns `{ completed: false }` without hashing when `now_ms_fn()` is at or
+/// past the deadline, so the caller can reschedule, and otherwise
+/// `{ completed: true, valid }`. The argon2 crate runs every pass in one
+/// call, so the clock is only checked before the computation starts; a
+/// verification that begins before the deadline always completes.
+#[wasm_bindgen]
+pub fn verify_with_deadline(
+    hash: &str,
+    password: &str,
+    deadline_ms: f64,
+    now_ms_fn: js_sys::Function,
+) -> Result<JsValue, JsValue> {
+    let now = now_ms_fn.call0(&JsValue::NULL)?.as_f64().ok_or_else(|| {
+        JsValue::from(PasswordError::InvalidInput("Clock callback must return a number".to_string()))
+    })?;
+
+    let result = if now >= deadline_ms {
+        DeadlineVerification {
+            completed: false,
+            valid: None,
+        }
+    } else {
+        DeadlineVerification {
+            completed: true,
+            valid: Some(verify(hash, password)?),
+        }
+    };
+
+    serde_wasm_bindgen::to_value(&result).map_err(|err| PasswordError::Serialization(err).into())
+}
+
+/// Returns the rotation epoch a hash was made with via the `epoch` option, or `null`
+#[wasm_bindgen]
+pub fn current_epoch(hash: &str) -> Result<JsValue, JsValue> {
+    let params = argon2id_params(hash).map_err(|err| {
+        error!("Failed to read hash epoch: {}", err);
+        JsValue::from(err)
+    })?;
+
+    Ok(epoch_from_params(&params).map_or(JsValue::NULL, JsValue::from))
+}
+
+/// Reports whether a hash predates the desired rotation epoch and should be rehashed
+///
+/// Hashes made without an epoch count as epoch 0. `login_check` applies the
+/// same rule when its `target` sets `epoch`.
+#[wasm_bindgen]
+pub fn needs_epoch_rehash(hash: &str, desired_epoch: u32) -> Result<bool, JsValue> {
+    let params = argon2id_params(hash).map_err(|err| {
+        error!("Failed to read hash epoch: {}", err);
+        JsValue::from(err)
+    })?;
+
+    Ok(epoch_from_params(&params).unwrap_or(0) < desired_epoch)
+}
+
+/// Internal function to read a millisecond timestamp from the JavaScript host
+///
+/// Prefers `performance.now()` for sub-millisecond resolution and falls back
+/// to `Date.now()` where it is unavailable.
+fn now_ms() -> f64 {
+    let performance = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("performance"))
+        .unwrap_or(JsValue::UNDEFINED);
+
+    js_sys::Reflect::get(&performance, &JsValue::from_str("now"))
+        .ok()
+        .and_then(|now| now.dyn_into::<js_sys::Function>().ok())
+        .and_then(|now| now.call0(&performance).ok())
+        .and_then(|value| value.as_f64())
+        .unwrap_or_else(js_sys::Date::now)
+}
+
+/// Upper bound on the lane count chosen by `parallelism_auto`
+const MAX_AUTO_PARALLELISM: u32 = 8;
+
+/// Internal function to pick a lane count from `navigator.hardwareConcurrency`
+///
+/// Falls back to 1 where the host does not report a core count. The result is
+/// clamped to `MAX_AUTO_PARALLELISM` and to the `memory_cost / 8` lanes
+/// argon2 allows for the given memory.
+fn auto_parallelism(memory_cost: u32) -> u32 {
+    let cores = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("navigator"))
+        .ok()
+        .filter(|navigator| navigator.is_object())
+        .and_then(|navigator| js_sys::Reflect::get(&navigator, &JsValue::from_str("hardwareConcurrency")).ok())
+        .and_then(|cores| cores.as_f64())
+        .filter(|cores| cores.is_finite() && *cores >= 1.0)
+        .map_or(1, |cores| cores as u32);
+
+    cores.min(MAX_AUTO_PARALLELISM).min(memory_cost / 8).max(1)
+}
+
+/// Internal function to run a caller-supplied password transform
+fn apply_transform(password: &str, transform: &js_sys::Function) -> Result<String, PasswordError> {
+    let transformed = transform
+        .call1(&JsValue::NULL, &JsValue::from_str(password))
+        .map_err(|_| PasswordError::InvalidInput("Password transform threw an exception".to_string()))?;
+
+    transformed
+        .as_string()
+        .ok_or_else(|| PasswordError::InvalidInput("Password transform must return a string".to_string()))
+}
+
+/// Upper bound on a streamed password, so a callback that never ends cannot spin forever
+const MAX_STREAMED_PASSWORD_LEN: usize = 64 * 1024;
+
+/// Internal function to drain a chunk callback into a zeroizing buffer
+fn read_password_stream(next_chunk: &js_sys::Function) -> Result<Zeroizing<Vec<u8>>, PasswordError> {
+    let mut password = Zeroizing::new(Vec::new());
+
+    loop {
+        let chunk: Uint8Array = next_chunk
+            .call0(&JsValue::NULL)
+            .map_err(|_| PasswordError::InvalidInput("Chunk callback threw an exception".to_string()))?
+            .dyn_into()
+            .map_err(|_| PasswordError::InvalidInput("Chunk callback must return a Uint8Array".to_string()))?;
+
+        let chunk_len = chunk.length() as usize;
+        if chunk_len == 0 {
+            return Ok(password);
+        }
+
+        let start = password.len();
+        let end = start + chunk_len;
+        if end > MAX_STREAMED_PASSWORD_LEN {
+            return Err(PasswordError::InvalidInput(format!(
+                "Streamed password exceeds {} bytes",
+                MAX_STREAMED_PASSWORD_LEN
+            )));
+        }
+
+        // Grow into a fresh buffer so the old allocation is zeroed, not just freed
+        if end > password.capacity() {
+            let mut grown = Zeroizing::new(Vec::with_capacity(end.max(password.capacity() * 2)));
+            grown.extend_from_slice(&password);
+            password = grown;
+        }
+        password.resize(end, 0);
+        chunk.copy_to(&mut password[start..end]);
+    }
+}
+
+/// Internal function to encode fields as big-endian u32 length + bytes, in order
+fn canonical_fields(fields: &[String]) -> Result<Vec<u8>, PasswordError> {
+    if fields.is_empty() {
+        return Err(PasswordError::InvalidInput("At least one field is required".to_string()));
+    }
+
+    let mut canonical = Vec::with_capacity(fields.iter().map(|field| 4 + field.len()).sum());
+    for field in fields {
+        let len = u32::try_from(field.len())
+            .map_err(|_| PasswordError::InvalidInput("Field is too long".to_string()))?;
+        canonical.extend_from_slice(&len.to_be_bytes());
+        canonical.extend_from_slice(field.as_bytes());
+    }
+    Ok(canonical)
+}
+
+/// Internal function to collect the rules of a password policy that a password fails
+fn check_password_policy(password: &str, policy: &PasswordPolicy) -> Result<(), PasswordError> {
+    let mut unmet = Vec::new();
+
+    if let Some(min_length) = policy.min_length
+        && password.chars().count() < min_length as usize
+    {
+        unmet.push(format!("at least {} characters", min_length));
+    }
+    if policy.require_upper && !password.chars().any(char::is_uppercase) {
+        unmet.push("an uppercase letter".to_string());
+    }
+    if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        unmet.push("a digit".to_string());
+    }
+    if policy.require_symbol && !password.chars().any(|c| !c.is_alphanumeric() && !c.is_whitespace()) {
+        unmet.push("a symbol".to_string());
+    }
+
+    if unmet.is_empty() {
+        Ok(())
+    } else {
+        Err(PasswordError::WeakPassword(unmet))
+    }
+}
+
+/// Internal function to re-encode a `hash_hex` string as PHC, if it is one
+fn hex_format_to_phc(hash: &str) -> Result<Option<String>, PasswordError> {
+    let is_hex = hash
+        .split('$')
+        .nth(1)
+        .is_some_and(|algorithm| algorithm.ends_with("-hex"));
+    if !is_hex {
+        return Ok(None);
+    }
+
+    HashParts::from_hex_format(hash).and_then(|parts| parts.to_phc()).map(Some)
+}
+
+/// Internal function to put a separately stored salt back in front of the tag segment
+fn reinsert_salt(params_and_tag: &str, salt: &[u8]) -> Result<String, PasswordError> {
+    let Some((params, tag)) = params_and_tag.rsplit_once('$') else {
+        return Err(PasswordError::InvalidInput("Missing tag segment".to_string()));
+    };
+    let salt = SaltString::encode_b64(salt).map_err(|err| PasswordError::BadSalt(err.to_string()))?;
+
+    Ok(format!("{}${}${}", params, salt.as_str(), tag))
+}
+
+/// Internal function to apply a named `verify_encodings` transform to a password
+fn encode_password(password: &str, encoding: &str) -> Result<String, PasswordError> {
+    match encoding {
+        "url" => Ok(percent_encode(password)),
+        "base64" => Ok(Base64::encode_string(password.as_bytes())),
+        "trim" => Ok(password.trim().to_string()),
+        "nfc" => Ok(password.nfc().collect()),
+        other => Err(PasswordError::InvalidInput(format!("Unknown password encoding '{}'", other))),
+    }
+}
+
+/// Internal function to percent-encode a string like JavaScript's `encodeURIComponent`
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.!~*'()".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Internal function to verify a password and each requested encoding of it
+fn argon2id_verify_encodings(hash: &str, password: &str, encodings: &[String]) -> Result<EncodingMatch, PasswordError> {
+    let password_hash = parse_phc(hash)?;
+
+    // Reject unknown names before spending a hash on any candidate
+    let candidates = encodings
+        .iter()
+        .map(|encoding| encode_password(password, encoding).map(|encoded| (encoding, encoded)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if argon2id_verify_parsed(&password_hash, password.as_bytes())? {
+        return Ok(EncodingMatch {
+            valid: true,
+            matched_encoding: None,
+        });
+    }
+
+    for (encoding, encoded) in candidates {
+        // A transform that leaves the password unchanged was already covered by the raw attempt
+        if !encoded.is_empty() && encoded != password && argon2id_verify_parsed(&password_hash, encoded.as_bytes())? {
+            return Ok(EncodingMatch {
+                valid: true,
+                matched_encoding: Some(encoding.clone()),
+            });
+        }
+    }
+
+    Ok(EncodingMatch {
+        valid: false,
+        matched_encoding: None,
+    })
+}
+
+/// Internal function to build the `pepper || password` input for legacy peppering
+fn prepend_pepper(pepper: &[u8], password: &[u8]) -> Result<Zeroizing<Vec<u8>>, PasswordError> {
+    if pepper.is_empty() {
+        return Err(PasswordError::InvalidInput("Pepper cannot be empty".to_string()));
+    }
+
+    let mut input = Zeroizing::new(Vec::with_capacity(pepper.len() + password.len()));
+    input.extend_from_slice(pepper);
+    input.extend_from_slice(password);
+    Ok(input)
+}
+
+/// Internal function to deserialize optional hash options from JavaScript
+fn parse_hash_options(options: JsValue) -> Result<Option<HashOptions>, PasswordError> {
+    serde_wasm_bindgen::from_value(options.clone()).map_err(|err| {
+        error!("Failed to deserialize options: {}", err);
+        // serde's message does not say which property was wrong, so look for it
+        match describe_options_field_error(&options) {
+            Some(message) => PasswordError::Serialization(serde_wasm_bindgen::Error::new(message)),
+            None => PasswordError::Serialization(err),
+        }
+    })
+}
+
+/// Expected JavaScript type of each `HashOptions` property, for error reporting
+const HASH_OPTION_FIELDS: [(&str, &str); 11] = [
+    ("time_cost", "integer"),
+    ("memory_cost", "integer"),
+    ("parallelism", "integer"),
+    ("pad_password_to", "integer"),
+    ("algorithm", "algorithm"),
+    ("allow_empty", "boolean"),
+    ("salt_len", "integer"),
+    ("context", "string"),
+    ("output_len", "integer"),
+    ("parallelism_auto", "boolean"),
+    ("epoch", "integer"),
+];
+
+/// Internal function to name the first options property with the wrong type
+fn describe_options_field_error(options: &JsValue) -> Option<String> {
+    if !options.is_object() {
+        return None;
+    }
+
+    HASH_OPTION_FIELDS.iter().find_map(|(name, expected)| {
+        let value = js_sys::Reflect::get(options, &JsValue::from_str(name)).ok()?;
+        if value.is_undefined() || value.is_null() {
+            return None;
+        }
+
+        let actual = value.js_typeof().as_string().unwrap_or_default();
+        match *expected {
+            "integer" => match value.as_f64() {
+                Some(number) if number.fract() == 0.0 && (0.0..=f64::from(u32::MAX)).contains(&number) => None,
+                Some(number) => Some(format!("{} must be an integer from 0 to {}, got {}", name, u32::MAX, number)),
+                None => Some(format!("{} must be a number, got {}", name, actual)),
+            },
+            "boolean" if value.as_bool().is_none() => Some(format!("{} must be a boolean, got {}", name, actual)),
+            "string" if value.as_string().is_none() => Some(format!("{} must be a string, got {}", name, actual)),
+            "algorithm" if value.as_string().is_none() && value.as_f64().is_none() => {
+                Some(format!("{} must be a string or number, got {}", name, actual))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Internal function to check that a digest or derived key is 32 or 64 bytes
+///
+/// `field` names the input in the error, e.g. `"Pre-hashed digest"`.
+fn validate_digest_len(bytes: &[u8], field: &str) -> Result<(), PasswordError> {
+    match bytes.len() {
+        32 | 64 => Ok(()),
+        len => Err(PasswordError::InvalidInput(format!(
+            "{} must be 32 or 64 bytes, got {}",
+            field,
+            len
+        ))),
+    }
+}
+
+/// Length in bytes of an AES-256 key
+const AES_256_KEY_LEN: usize = 32;
+
+/// Internal function to map a target algorithm name to its key length in bytes
+fn key_len_for(algorithm: &str) -> Result<usize, PasswordError> {
+    match algorithm {
+        "aes256" | "chacha20" | "hmac_sha256" => Ok(32),
+        other => Err(PasswordError::InvalidInput(format!(
+            "Unknown key algorithm: {}",
+            other
+        ))),
+    }
+}
+
+/// Internal function to estimate the bytes Argon2 allocates for a memory cost in KiB
+fn estimated_memory_bytes(memory_cost: u32) -> u64 {
+    u64::from(memory_cost) * 1024
+}
+
+/// Largest accepted `pad_password_to`, so one option cannot force a huge allocation
+const MAX_PAD_BUCKET: u32 = 4096;
+
+/// Internal function to pad the password into a fixed-size bucket
+///
+/// Argon2's initial Blake2b step takes time proportional to the password
+/// length, which can weakly leak it. With a bucket size set, the input becomes
+/// `len (u32 big-endian) || password || zeros`, rounded up to a multiple of
+/// the bucket. The length prefix keeps distinct passwords from colliding.
+///
+/// This only hides the length within a bucket, and the same bucket size must
+/// be used at verify time or the password will not match.
+fn pad_password(password: &[u8], pad_password_to: Option<u32>) -> Result<Vec<u8>, PasswordError> {
+    let bucket = match pad_password_to {
+        None => return Ok(password.to_vec()),
+        Some(0) => {
+            return Err(PasswordError::InvalidInput(
+                "pad_password_to must be greater than 0".to_string(),
+            ))
+        }
+        Some(bucket) if bucket > MAX_PAD_BUCKET => {
+            return Err(PasswordError::InvalidInput(format!(
+                "pad_password_to must be at most {}",
+                MAX_PAD_BUCKET
+            )))
+        }
+        Some(bucket) => bucket as usize,
+    };
+
+    let len = u32::try_from(password.len())
+        .map_err(|_| PasswordError::InvalidInput("Password is too long to pad".to_string()))?;
+
+    let unpadded_len = 4 + password.len();
+    let padded_len = unpadded_len.div_ceil(bucket) * bucket;
+
+    let mut padded = Vec::with_capacity(padded_len);
+    padded.extend_from_slice(&len.to_be_bytes());
+    padded.extend_from_slice(password);
+    padded.resize(padded_len, 0);
+    Ok(padded)
+}
+
+/// Internal function to apply the configured preprocessing to a password
+///
+/// Padding runs first. A `context` then replaces the result with
+/// HKDF-SHA256(password, info = context), so hashes made for one context
+/// never verify under another.
+fn preprocess_password(
+    password: &[u8],
+    pad_password_to: Option<u32>,
+    context: Option<&str>,
+) -> Result<Vec<u8>, PasswordError> {
+    let password = pad_password(password, pad_password_to)?;
+
+    let Some(context) = context else {
+        return Ok(password);
+    };
+
+    let mut derived = vec![0u8; 32];
+    Hkdf::<Sha256>::new(None, &password)
+        .expand(context.as_bytes(), &mut derived)
+        .map_err(|err| PasswordError::InvalidParams(err.to_string()))?;
+    Ok(derived)
+}
+
+/// Internal function to build an Argon2 instance, optionally keyed with a secret
+/// and bound to associated data
+fn argon2id_keyed_instance<'k>(
+    options: Option<&HashOptions>,
+    secret: Option<&'k [u8]>,
+    data: Option<&[u8]>,
+) -> Result<Argon2<'k>, PasswordError> {
+    let (algorithm, mut builder) = match options {
+        Some(opts) => {
+            let parallelism = match opts.parallelism {
+                0 if opts.parallelism_auto => auto_parallelism(opts.memory_cost),
+                parallelism => parallelism,
+            };
+
+            // Validate parameters to prevent invalid configurations
+            if opts.memory_cost < 8 || opts.time_cost == 0 || parallelism == 0 {
+                return Err(PasswordError::InvalidInput(
+                    "Invalid hash parameters: memory_cost must be >= 8, time_cost and parallelism must be > 0".to_string(),
+                ));
+            }
+
+            let mut builder = ParamsBuilder::new();
+            builder
+                .m_cost(opts.memory_cost)
+                .t_cost(opts.time_cost)
+                .p_cost(parallelism);
+            if let Some(output_len) = opts.output_len {
+                builder.output_len(output_len as usize);
+            }
+
+            (opts.algorithm.unwrap_or(Algorithm::Argon2id).into(), builder)
+        }
+        None => (argon2::Algorithm::Argon2id, ParamsBuilder::new()),
+    };
+
+    if let Some(data) = data {
+        builder.data(AssociatedData::new(data)?);
+    }
+    let params = builder.build()?;
+
+    let argon2 = match secret {
+        Some(secret) => Argon2::new_with_secret(secret, algorithm, argon2::Version::V0x13, params)?,
+        None => Argon2::new(algorithm, argon2::Version::V0x13, params),
+    };
+
+    Ok(argon2)
+}
+
+/// Internal function to hash a password with Argon2id
+fn argon2id_hash(password: &[u8], options: Option<HashOptions>) -> Result<String, PasswordError> {
+    argon2id_keyed_hash(password, None, options)
+}
+
+/// Internal function to hash a password with Argon2id, optionally keyed with a secret
+fn argon2id_keyed_hash(
+    password: &[u8],
+    secret: Option<&[u8]>,
+    options: Option<HashOptions>,
+) -> Result<String, PasswordError> {
+    let salt = generate_salt(options.as_ref())?;
+
+    argon2id_hash_with_salt(password, secret, None, &salt, options)
+}
+
+/// Longest salt, in bytes, that fits in a PHC string's salt field
+const MAX_SALT_LEN: usize = Salt::MAX_LENGTH * 3 / 4;
+
+/// Internal function to generate a random salt of the requested length
+fn generate_salt(options: Option<&HashOptions>) -> Result<SaltString, PasswordError> {
+    generate_salt_with(&mut OsRng, options)
+}
+
+/// Internal function to generate a salt of the requested length from a given RNG
+fn generate_salt_with(rng: &mut impl RngCore, options: Option<&HashOptions>) -> Result<SaltString, PasswordError> {
+    let len = salt_len(options)?;
+
+    let mut bytes = [0u8; MAX_SALT_LEN];
+    rng.try_fill_bytes(&mut bytes[..len])
+        .map_err(|err| PasswordError::RngUnavailable(err.to_string()))?;
+
+    SaltString::encode_b64(&bytes[..len]).map_err(|err| PasswordError::InvalidParams(err.to_string()))
+}
+
+/// Internal function to resolve and bounds-check the requested salt length
+fn salt_len(options: Option<&HashOptions>) -> Result<usize, PasswordError> {
+    let len = options
+        .and_then(|opts| opts.salt_len)
+        .map_or(argon2::RECOMMENDED_SALT_LEN, |len| len as usize);
+
+    if len < argon2::MIN_SALT_LEN {
+        return Err(PasswordError::InvalidInput(format!(
+            "Salt length must be at least {} bytes, got {}",
+            argon2::MIN_SALT_LEN,
+            len
+        )));
+    }
+    if len > MAX_SALT_LEN {
+        return Err(PasswordError::InvalidInput(format!(
+            "Salt length must be at most {} bytes, got {}",
+            MAX_SALT_LEN, len
+        )));
+    }
+
+    Ok(len)
+}
+
+/// Internal function to hash a password with a caller-supplied salt
+fn argon2id_hash_with_salt(
+    password: &[u8],
+    secret: Option<&[u8]>,
+    data: Option<&[u8]>,
+    salt: &SaltString,
+    options: Option<HashOptions>,
+) -> Result<String, PasswordError> {
+    // Both live in the data segment; an epoch is 4 bytes and a timestamp 8, so they never alias
+    let epoch = options.as_ref().and_then(|opts| opts.epoch).map(u32::to_be_bytes);
+    let data = match (data, &epoch) {
+        (Some(_), Some(_)) => {
+            return Err(PasswordError::InvalidInput(
+                "epoch cannot be combined with other associated data such as a timestamp".to_string(),
+            ));
+        }
+        (data, epoch) => data.or(epoch.as_ref().map(|epoch| epoch.as_slice())),
+    };
+    let argon2 = argon2id_keyed_instance(options.as_ref(), secret, data)?;
+    let params = argon2.params();
+    let _reservation = MemoryReservation::acquire(estimated_memory_bytes(params.m_cost()))?;
+    let password = match options.as_ref() {
+        Some(opts) => preprocess_password(password, opts.pad_password_to, opts.context.as_deref())?,
+        None => password.to_vec(),
+    };
+
+    let mut salt_buf = [0u8; Salt::MAX_LENGTH];
+    let salt_bytes = salt
+        .as_salt()
+        .decode_b64(&mut salt_buf)
+        .map_err(|err| PasswordError::BadSalt(err.to_string()))?;
+
+    let mut blocks = allocate_blocks(params)?;
+    let mut tag = vec![0u8; params.output_len().unwrap_or(Params::DEFAULT_OUTPUT_LEN)];
+    emit_event("hash_started", params, None);
+    let start = now_ms();
+    argon2
+        .hash_password_into_with_memory(&password, salt_bytes, &mut tag, &mut blocks)
+        .map_err(|err| match err {
+            argon2::Error::PwdTooLong => {
+                PasswordError::InvalidInput("Password exceeds the maximum Argon2 length".to_string())
+            }
+            err => PasswordError::InvalidParams(err.to_string()),
+        })?;
+    emit_event("hash_completed", params, Some(now_ms() - start));
+
+    let algorithm: argon2::Algorithm =
+        options.as_ref().and_then(|opts| opts.algorithm).unwrap_or(Algorithm::Argon2id).into();
+    let password_hash = PasswordHash {
+        algorithm: algorithm.ident(),
+        version: Some(argon2::Version::V0x13 as u32),
+        params: ParamsString::try_from(params).map_err(|err| PasswordError::InvalidParams(err.to_string()))?,
+        salt: Some(salt.as_salt()),
+        hash: Some(Output::new(&tag).map_err(|err| PasswordError::InvalidParams(err.to_string()))?),
+    };
+
+    Ok(password_hash.to_string())
+}
+
+/// Internal function to allocate Argon2's working memory without aborting on failure
+///
+/// A failed allocation would otherwise trap as an uncatchable `unreachable`,
+/// so the blocks are reserved fallibly and handed to argon2 explicitly.
+fn allocate_blocks(params: &Params) -> Result<Vec<Block>, PasswordError> {
+    let count = params.block_count();
+
+    let mut blocks = Vec::new();
+    blocks.try_reserve_exact(count).map_err(|_| {
+        PasswordError::ResourceExhausted(format!(
+            "Could not allocate {} KiB of Argon2 memory",
+            params.m_cost()
+        ))
+    })?;
+    blocks.resize(count, Block::default());
+    Ok(blocks)
+}
+
+/// Internal function to derive raw key bytes with Argon2id
+fn argon2id_derive(
+    passphrase: &[u8],
+    salt: &[u8],
+    key_len: usize,
+    options: Option<HashOptions>,
+) -> Result<Vec<u8>, PasswordError> {
+    argon2id_derive_with_data(passphrase, salt, None, key_len, options.as_ref())
+}
+
+/// Internal function to derive raw key bytes bound to optional associated data
+fn argon2id_derive_with_data(
+    passphrase: &[u8],
+    salt: &[u8],
+    data: Option<&[u8]>,
+    key_len: usize,
+    options: Option<&HashOptions>,
+) -> Result<Vec<u8>, PasswordError> {
+    let argon2 = argon2id_keyed_instance(options, None, data)?;
+    let mut blocks = allocate_blocks(argon2.params())?;
+
+    let mut key = vec![0u8; key_len];
+    argon2.hash_password_into_with_memory(passphrase, salt, &mut key, &mut blocks)?;
+    Ok(key)
+}
+
+/// Domain-separation prefix for the salt `blind_index` derives from a namespace key
+const BLIND_INDEX_SALT_LABEL: &[u8] = b"argon2id-wasm blind_index salt";
+
+/// Internal function to compute a keyed, deterministic index for a value
+fn argon2id_blind_index(value: &[u8], namespace_key: &[u8], options: Option<&HashOptions>) -> Result<Vec<u8>, PasswordError> {
+    let digest = Blake2b512::new()
+        .chain_update(BLIND_INDEX_SALT_LABEL)
+        .chain_update(namespace_key)
+        .finalize();
+    let salt = &digest[..salt_len(options)?];
+
+    let argon2 = argon2id_keyed_instance(options, Some(namespace_key), None)?;
+    let mut blocks = allocate_blocks(argon2.params())?;
+
+    let mut index = vec![0u8; argon2.params().output_len().unwrap_or(Params::DEFAULT_OUTPUT_LEN)];
+    argon2.hash_password_into_with_memory(value, salt, &mut index, &mut blocks)?;
+    Ok(index)
+}
+
+/// Internal function to MAC the algorithm, version and params of a PHC string
+///
+/// Covers everything before the salt, so editing any parameter breaks it.
+fn params_mac(phc: &str, secret: &[u8]) -> Result<Hmac<Sha256>, PasswordError> {
+    let header = phc
+        .rsplitn(3, '$')
+        .nth(2)
+        .ok_or_else(|| PasswordError::InvalidInput("Malformed hash".to_string()))?;
+
+    // HMAC accepts keys of any length, so this cannot fail
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).map_err(|err| PasswordError::InvalidParams(err.to_string()))?;
+    mac.update(header.as_bytes());
+    Ok(mac)
+}
+
+/// Length of the Argon2id-derived HMAC key in bytes
+const MAC_KEY_LEN: usize = 32;
+
+/// Internal function to key an HMAC-SHA256 from a passphrase and feed it `data`
+fn argon2id_mac(
+    data: &[u8],
+    passphrase: &[u8],
+    salt: &[u8],
+    options: Option<HashOptions>,
+) -> Result<Hmac<Sha256>, PasswordError> {
+    let key = argon2id_derive(passphrase, salt, MAC_KEY_LEN, options)?;
+
+    // HMAC accepts keys of any length, so this cannot fail
+    let mut mac = Hmac::<Sha256>::new_from_slice(&key)
+        .map_err(|err| PasswordError::InvalidParams(err.to_string()))?;
+    mac.update(data);
+    Ok(mac)
+}
+
+/// Internal function to verify a password against a hash
+fn argon2id_verify(options: &VerifyRequest) -> Result<bool, PasswordError> {
+    argon2id_verify_bytes(&options.hash, options.password.as_bytes())
+}
+
+/// Prefix of the version 1 credential envelope
+const CREDENTIAL_ENVELOPE_V1: &str = "v1:";
+
+/// Internal function to strip a credential envelope, rejecting unknown versions
+fn unwrap_envelope(credential: &str) -> Result<&str, PasswordError> {
+    if let Some(phc) = credential.strip_prefix(CREDENTIAL_ENVELOPE_V1) {
+        return Ok(phc);
+    }
+
+    // Anything else shaped like `v<digits>:` is a newer envelope than we understand
+    if let Some((version, _)) = credential.split_once(':')
+        && let Some(number) = version.strip_prefix('v')
+        && !number.is_empty()
+        && number.bytes().all(|byte| byte.is_ascii_digit())
+    {
+        return Err(PasswordError::InvalidInput(format!(
+            "Unsupported credential envelope version '{}'",
+            version
+        )));
+    }
+
+    Ok(credential)
+}
+
+/// Internal function to reduce any hash form `verify` accepts to a bare PHC string
+///
+/// Trims surrounding ASCII whitespace, strips the credential envelope and
+/// re-encodes `hash_hex` strings, so callers parse the result only once.
+fn normalize_hash(hash: &str) -> Result<Cow<'_, str>, PasswordError> {
+    let hash = unwrap_envelope(hash.trim_ascii())?;
+
+    Ok(match hex_format_to_phc(hash)? {
+        Some(phc) => Cow::Owned(phc),
+        None => Cow::Borrowed(hash),
+    })
+}
+
+/// Internal function to check a hash's decoded tag against the expected length
+fn check_tag_len(hash: &str, expected: Option<u32>) -> Result<(), PasswordError> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+
+    let actual = parse_phc(hash)?.hash.map_or(0, |output| output.len());
+    if actual != expected as usize {
+        return Err(PasswordError::InconsistentTagLength(format!(
+            "expected {} bytes, hash has {}",
+            expected, actual
+        )));
+    }
+    Ok(())
+}
+
+/// Internal function to parse a PHC string
+fn parse_phc(hash: &str) -> Result<PasswordHash<'_>, PasswordError> {
+    PasswordHash::new(hash).map_err(|err| classify_phc_error(hash, err))
+}
+
+/// Internal function to map an error from using a parsed hash to the segment at fault
+fn classify_parsed_hash_error(err: argon2::password_hash::Error) -> PasswordError {
+    use argon2::password_hash::Error;
+
+    let message = err.to_string();
+    match err {
+        // The output is decoded at parse time, so base64 errors here come from the salt
+        Error::B64Encoding(_) | Error::SaltInvalid(_) => PasswordError::BadSalt(message),
+        Error::OutputSize { .. } => PasswordError::BadTag(message),
+        Error::Algorithm => PasswordError::BadAlgorithm(message),
+        Error::ParamNameDuplicated
+        | Error::ParamNameInvalid
+        | Error::ParamValueInvalid(_)
+        | Error::ParamsMaxExceeded
+        | Error::Version => PasswordError::BadParams(message),
+        _ => PasswordError::InvalidParams(message),
+    }
+}
+
+/// Internal function to map a PHC parse error to the segment that caused it
+fn classify_phc_error(hash: &str, err: argon2::password_hash::Error) -> PasswordError {
+    use argon2::password_hash::{Error, Ident};
+
+    let message = err.to_string();
+    match err {
+        Error::SaltInvalid(_) => PasswordError::BadSalt(message),
+        Error::B64Encoding(_) | Error::OutputSize { .. } => PasswordError::BadTag(message),
+        Error::Algorithm => PasswordError::BadAlgorithm(message),
+        // Identifiers and param names share this error; the algorithm is the first segment
+        Error::ParamNameInvalid => match hash.split('$').nth(1).map(Ident::try_from) {
+            Some(Ok(_)) => PasswordError::BadParams(message),
+            _ => PasswordError::BadAlgorithm(message),
+        },
+        Error::ParamNameDuplicated | Error::ParamValueInvalid(_) | Error::ParamsMaxExceeded | Error::Version => {
+            PasswordError::BadParams(message)
+        }
+        _ => PasswordError::InvalidParams(message),
+    }
+}
+
+/// Internal function to verify raw password bytes against a hash
+fn argon2id_verify_bytes(hash: &str, password: &[u8]) -> Result<bool, PasswordError> {
+    let password_hash = parse_phc(hash)?;
+
+    argon2id_verify_parsed(&password_hash, password)
+}
+
+/// Internal function to verify raw password bytes against an already parsed hash
+fn argon2id_verify_parsed(password_hash: &PasswordHash, password: &[u8]) -> Result<bool, PasswordError> {
+    argon2id_keyed_verify_parsed(password_hash, password, None)
+}
+
+/// Internal function to verify raw password bytes against a parsed hash, optionally keyed
+fn argon2id_keyed_verify_parsed(
+    password_hash: &PasswordHash,
+    password: &[u8],
+    secret: Option<&[u8]>,
+) -> Result<bool, PasswordError> {
+    // A well-formed non-Argon2 hash is reported by name so callers can route it elsewhere
+    if argon2::Algorithm::try_from(password_hash.algorithm).is_err() {
+        return Err(PasswordError::UnsupportedAlgorithm(password_hash.algorithm.to_string()));
+    }
+
+    let start = now_ms();
+    argon2id_keyed_instance(None, secret, None)?
+        .verify_password(password, password_hash)
+        .map(|_| true)
+        .or_else(|err| match err {
+            argon2::password_hash::Error::Password => {
+                if let Ok(params) = Params::try_from(password_hash) {
+                    emit_event("verify_mismatch", &params, Some(now_ms() - start));
+                }
+                Ok(false)
+            }
+            err => Err(classify_parsed_hash_error(err)),
+        })
+}
+
+/// Internal function to pass a diagnostic event to the registered sink, if any
+fn emit_event(event: &str, params: &Params, elapsed_ms: Option<f64>) {
+    // Cloned out so a callback that replaces the sink does not hit a held borrow
+    let Some(sink) = EVENT_SINK.with(|cell| cell.borrow().clone()) else {
+        return;
+    };
+
+    let event = DiagnosticEvent {
+        event: event.to_string(),
+        memory_cost: params.m_cost(),
+        time_cost: params.t_cost(),
+        parallelism: params.p_cost(),
+        elapsed_ms,
+    };
+    match serde_wasm_bindgen::to_value(&event) {
+        Ok(value) => {
+            if let Err(err) = sink.call1(&JsValue::NULL, &value) {
+                warn!("Event sink threw: {:?}", err);
+            }
+        }
+        Err(err) => warn!("Failed to serialize diagnostic event: {}", err),
+    }
+}
+
+/// Internal function to verify and time each request in a batch
+fn argon2id_verify_batch_timed(requests: &[VerifyRequest]) -> Result<TimedBatchVerification, PasswordError> {
+    let mut results = Vec::with_capacity(requests.len());
+    let mut per_item_ms = Vec::with_capacity(requests.len());
+
+    let batch_start = now_ms();
+    for (index, request) in requests.iter().enumerate() {
+        let hash = normalize_hash(&request.hash).map_err(|err| err.at_index(index))?;
+        if hash.is_empty() || request.password.is_empty() {
+            return Err(PasswordError::InvalidInput("Hash and password cannot be empty".to_string()).at_index(index));
+        }
+
+        let start = now_ms();
+        let valid = argon2id_verify_bytes(&hash, request.password.as_bytes()).map_err(|err| err.at_index(index))?;
+        per_item_ms.push(now_ms() - start);
+        results.push(valid);
+    }
+
+    Ok(TimedBatchVerification {
+        results,
+        total_ms: now_ms() - batch_start,
+        per_item_ms,
+    })
+}
+
+/// Internal function to allocate and release a hash-sized buffer
+fn argon2id_prewarm(options: Option<HashOptions>) -> Result<(), PasswordError> {
+    let argon2 = argon2id_keyed_instance(options.as_ref(), None, None)?;
+    let bytes = estimated_memory_bytes(argon2.params().m_cost());
+    let _reservation = MemoryReservation::acquire(bytes)?;
+
+    std::hint::black_box(allocate_blocks(argon2.params())?);
+
+    Ok(())
+}
+
+/// Width of the `t` field in a `pack_params` number
+const PACKED_TIME_BITS: u32 = 13;
+/// Width of the `p` field in a `pack_params` number
+const PACKED_PARALLELISM_BITS: u32 = 8;
+
+/// Internal function to pack m, t and p into the layout documented on `pack_params`
+fn argon2id_pack_params(options: &HashOptions) -> Result<f64, PasswordError> {
+    if options.time_cost >= 1 << PACKED_TIME_BITS {
+        return Err(PasswordError::InvalidParams(format!(
+            "time_cost {} does not fit in {} bits",
+            options.time_cost, PACKED_TIME_BITS
+        )));
+    }
+    if options.parallelism >= 1 << PACKED_PARALLELISM_BITS {
+        return Err(PasswordError::InvalidParams(format!(
+            "parallelism {} does not fit in {} bits",
+            options.parallelism, PACKED_PARALLELISM_BITS
+        )));
+    }
+    Params::new(options.memory_cost, options.time_cost, options.parallelism, None)?;
+
+    let packed = (u64::from(options.memory_cost) << (PACKED_TIME_BITS + PACKED_PARALLELISM_BITS))
+        | (u64::from(options.time_cost) << PACKED_PARALLELISM_BITS)
+        | u64::from(options.parallelism);
+    Ok(packed as f64)
+}
+
+/// Internal function to unpack the layout documented on `pack_params`
+fn argon2id_unpack_params(packed: f64) -> Result<HashOptions, PasswordError> {
+    // 2^53, the first integer a JS number cannot represent exactly
+    const LIMIT: f64 = 9_007_199_254_740_992.0;
+    if !(0.0..LIMIT).contains(&packed) || packed.fract() != 0.0 {
+        return Err(PasswordError::InvalidInput(format!(
+            "Packed params must be an integer between 0 and 2^53, got {}",
+            packed
+        )));
+    }
+
+    let packed = packed as u64;
+    let options = HashOptions {
+        memory_cost: (packed >> (PACKED_TIME_BITS + PACKED_PARALLELISM_BITS)) as u32,
+        time_cost: ((packed >> PACKED_PARALLELISM_BITS) & ((1 << PACKED_TIME_BITS) - 1)) as u32,
+        parallelism: (packed & ((1 << PACKED_PARALLELISM_BITS) - 1)) as u32,
+        ..Default::default()
+    };
+    Params::new(options.memory_cost, options.time_cost, options.parallelism, None)?;
+
+    Ok(options)
+}
+
+/// Internal function to parse and validate a compact `m=...,t=...,p=...` string
+fn argon2id_parse_param_string(s: &str) -> Result<HashOptions, PasswordError> {
+    let mut options = HashOptions::default();
+    let mut seen = [false; 3];
+
+    for pair in s.split(',').map(str::trim) {
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| PasswordError::InvalidInput(format!("Expected key=value, got '{}'", pair)))?;
+        let value: u32 = value
+            .trim()
+            .parse()
+            .map_err(|_| PasswordError::InvalidInput(format!("Invalid value for '{}': '{}'", key, value)))?;
+
+        let (slot, field) = match key.trim() {
+            "m" => (0, &mut options.memory_cost),
+            "t" => (1, &mut options.time_cost),
+            "p" => (2, &mut options.parallelism),
+            other => return Err(PasswordError::InvalidInput(format!("Unknown parameter '{}'", other))),
+        };
+        if seen[slot] {
+            return Err(PasswordError::InvalidInput(format!("Duplicate parameter '{}'", key.trim())));
+        }
+        seen[slot] = true;
+        *field = value;
+    }
+
+    argon2id_keyed_instance(Some(&options), None, None)?;
+    Ok(options)
+}
+
+/// Internal function to derive the `verify_cached` key for a hash and password
+fn verify_cache_key(hash: &str, password: &str) -> Result<[u8; 64], PasswordError> {
+    let secret = {
+        let mut secret = VERIFY_CACHE_SECRET.lock().unwrap_or_else(|err| err.into_inner());
+        match *secret {
+            Some(existing) => existing,
+            None => {
+                let mut fresh = [0u8; 32];
+                OsRng
+                    .try_fill_bytes(&mut fresh)
+                    .map_err(|err| PasswordError::RngUnavailable(err.to_string()))?;
+                *secret = Some(fresh);
+                fresh
+            }
+        }
+    };
+
+    // Blake2b accepts keys up to 64 bytes, so this cannot fail
+    let mut mac = Blake2bMac512::new_from_slice(&secret).map_err(|err| PasswordError::InvalidParams(err.to_string()))?;
+    // Length-prefix the hash so (hash, password) pairs cannot collide by shifting bytes
+    Mac::update(&mut mac, &(hash.len() as u64).to_be_bytes());
+    Mac::update(&mut mac, hash.as_bytes());
+    Mac::update(&mut mac, password.as_bytes());
+    Ok(mac.finalize().into_bytes().into())
+}
+
+/// Memory size units and their size in KiB, smallest first
+const MEMORY_UNITS: [(&str, u32); 3] = [("KiB", 1), ("MiB", 1024), ("GiB", 1024 * 1024)];
+
+/// Internal function to parse a human-readable memory size into KiB
+fn argon2id_parse_memory(s: &str) -> Result<u32, PasswordError> {
+    let invalid = || PasswordError::InvalidInput(format!("Invalid memory size '{}', expected e.g. 64MiB", s));
+
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let (number, unit) = s.split_at(split);
+
+    let value: u32 = number.parse().map_err(|_| invalid())?;
+    let (_, kib) = MEMORY_UNITS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(unit.trim()))
+        .ok_or_else(invalid)?;
+
+    value
+        .checked_mul(*kib)
+        .ok_or_else(|| PasswordError::InvalidInput(format!("Memory size '{}' is too large", s)))
+}
+
+/// Internal function to hash a batch of passwords with one shared salt
+fn argon2id_hash_batch_fixed_salt(
+    passwords: &[String],
+    salt: &[u8],
+    options: Option<HashOptions>,
+) -> Result<Vec<String>, PasswordError> {
+    let salt = SaltString::encode_b64(salt).map_err(|err| PasswordError::InvalidInput(format!("Invalid salt: {}", err)))?;
+    let allow_empty = options.as_ref().is_some_and(|opts| opts.allow_empty);
+
+    passwords
+        .iter()
+        .enumerate()
+        .map(|(index, password)| {
+            if password.is_empty() && !allow_empty {
+                return Err(PasswordError::InvalidInput("Password cannot be empty".to_string()).at_index(index));
+            }
+
+            argon2id_hash_with_salt(password.as_bytes(), None, None, &salt, options.clone())
+                .map_err(|err| err.at_index(index))
+        })
+        .collect()
+}
+
+/// Internal function to hash a value with a salt derived from its namespace
+fn argon2id_hash_namespaced(value: &[u8], namespace: &[u8], options: Option<HashOptions>) -> Result<String, PasswordError> {
+    let len = salt_len(options.as_ref())?;
+    let digest = Blake2b512::new().chain_update(namespace).chain_update(value).finalize();
+    let salt = SaltString::encode_b64(&digest[..len]).map_err(|err| PasswordError::InvalidParams(err.to_string()))?;
+
+    argon2id_hash_with_salt(value, None, None, &salt, options)
+}
+
+/// Internal function to collect the distinct algorithms and versions in a hash set
+fn argon2id_audit_homogeneity(hashes: &[String]) -> HomogeneityAudit {
+    let mut algorithms: Vec<String> = Vec::new();
+    let mut versions: Vec<u32> = Vec::new();
+    let mut malformed_count = 0;
+
+    for hash in hashes {
+        let Ok(parsed) = parse_phc(hash) else {
+            malformed_count += 1;
+            continue;
+        };
+
+        let algorithm = parsed.algorithm.as_str();
+        if !algorithms.iter().any(|seen| seen == algorithm) {
+            algorithms.push(algorithm.to_string());
+        }
+
+        let version = parsed.version.unwrap_or(argon2::Version::default() as u32);
+        if !versions.contains(&version) {
+            versions.push(version);
+        }
+    }
+
+    HomogeneityAudit {
+        homogeneous: algorithms.len() <= 1 && versions.len() <= 1,
+        algorithms,
+        versions,
+        malformed_count,
+    }
+}
+
+/// Internal function to count stored hashes per algorithm, version and param set
+fn argon2id_param_histogram(hashes: &[String]) -> ParamHistogram {
+    let mut counts: BTreeMap<(String, u32, u32, u32, u32), u32> = BTreeMap::new();
+    let mut malformed = 0;
+
+    for hash in hashes {
+        let Some((parsed, params)) = parse_phc(hash)
+            .ok()
+            .and_then(|parsed| Params::try_from(&parsed).ok().map(|params| (parsed, params)))
+        else {
+            malformed += 1;
+            continue;
+        };
+
+        let version = parsed.version.unwrap_or(argon2::Version::default() as u32);
+        let key = (
+            parsed.algorithm.to_string(),
+            version,
+            params.m_cost(),
+            params.t_cost(),
+            params.p_cost(),
+        );
+        *counts.entry(key).or_default() += 1;
+    }
+
+    let mut buckets: Vec<ParamBucket> = counts
+        .into_iter()
+        .map(|((algorithm, version, memory_cost, time_cost, parallelism), count)| ParamBucket {
+            algorithm,
+            version,
+            memory_cost,
+            time_cost,
+            parallelism,
+            count,
+        })
+        .collect();
+    // Most common first; the sort is stable, so ties keep the key order
+    buckets.sort_by_key(|bucket| std::cmp::Reverse(bucket.count));
+
+    ParamHistogram { buckets, malformed }
+}
+
+/// Internal function to verify against a hash with overridden m, t or p values
+fn argon2id_verify_with_param_override(
+    hash: &str,
+    password: &[u8],
+    param_override: &ParamOverride,
+) -> Result<bool, PasswordError> {
+    let mut password_hash = parse_phc(hash)?;
+
+    let mut params = ParamsString::new();
+    for (name, value) in password_hash.params.iter() {
+        let replacement = match name.as_str() {
+            "m" => param_override.memory_cost,
+            "t" => param_override.time_cost,
+            "p" => param_override.parallelism,
+            _ => None,
+        };
+        match replacement {
+            Some(replacement) => params.add_decimal(name, replacement),
+            None => params.add_str(name, value.as_str()),
+        }
+        .map_err(classify_parsed_hash_error)?;
+    }
+    password_hash.params = params;
+
+    argon2id_verify_parsed(&password_hash, password)
+}
+
+/// Internal function to recompute and compare a legacy concatenated credential
+fn argon2id_verify_legacy_concat(
+    stored: &str,
+    password: &[u8],
+    options: Option<HashOptions>,
+) -> Result<bool, PasswordError> {
+    let (salt, tag) = stored
+        .split_once('$')
+        .ok_or_else(|| PasswordError::InvalidInput("Legacy credential must be salt$tag".to_string()))?;
+    let salt = decode_legacy_b64(salt, "salt")?;
+    let tag = decode_legacy_b64(tag, "tag")?;
+
+    let expected = Output::new(&tag).map_err(|err| PasswordError::InvalidInput(format!("Invalid tag: {}", err)))?;
+    let raw = argon2id_derive(password, &salt, tag.len(), options)?;
+
+    // Output's equality is constant-time
+    let computed = Output::new(&raw).map_err(|err| PasswordError::InvalidParams(err.to_string()))?;
+    Ok(computed == expected)
+}
+
+/// Internal function to recompute a raw verifier and compare it with the stored one
+fn argon2id_check_verifier(
+    verifier: &[u8],
+    password: &[u8],
+    salt: &[u8],
+    options: Option<HashOptions>,
+) -> Result<bool, PasswordError> {
+    let expected = Output::new(verifier).map_err(|err| PasswordError::InvalidInput(format!("Invalid verifier: {}", err)))?;
+    let raw = argon2id_derive(password, salt, verifier.len(), options)?;
+
+    // Output's equality is constant-time
+    let computed = Output::new(&raw).map_err(|err| PasswordError::InvalidParams(err.to_string()))?;
+    Ok(computed == expected)
+}
+
+/// Internal function to decode one field of a legacy credential
+fn decode_legacy_b64(field: &str, name: &str) -> Result<Vec<u8>, PasswordError> {
+    Base64::decode_vec(field)
+        .or_else(|_| Base64Unpadded::decode_vec(field))
+        .map_err(|_| PasswordError::InvalidInput(format!("Legacy {} is not valid base64", name)))
+}
+
+/// Internal function to verify, retrying with a re-encoded salt on parse failure
+fn argon2id_verify_lenient(hash: &str, password: &[u8]) -> Result<bool, PasswordError> {
+    let err = match argon2id_verify_bytes(hash, password) {
+        Ok(valid) => return Ok(valid),
+        Err(err) => err,
+    };
+
+    match normalize_salt_encoding(hash) {
+        Some(normalized) => argon2id_verify_bytes(&normalized, password),
+        None => Err(err),
+    }
+}
+
+/// Internal function to rewrite a URL-safe or padded salt segment as standard B64
+///
+/// Returns `None` if the salt is already standard or matches no known variant.
+fn normalize_salt_encoding(hash: &str) -> Option<String> {
+    let mut segments = hash.rsplitn(3, '$');
+    let tag = segments.next()?;
+    let salt = segments.next()?;
+    let prefix = segments.next()?;
+
+    if Base64Unpadded::decode_vec(salt).is_ok() {
+        return None;
+    }
+
+    let bytes = Base64UrlUnpadded::decode_vec(salt)
+        .or_else(|_| Base64::decode_vec(salt))
+        .or_else(|_| Base64Url::decode_vec(salt))
+        .ok()?;
+    let salt = SaltString::encode_b64(&bytes).ok()?;
+
+    Some(format!("{}${}${}", prefix, salt.as_str(), tag))
+}
+
+/// Internal function to verify a password against two hashes
+fn argon2id_would_both_accept(hash_a: &str, hash_b: &str, password: &[u8]) -> Result<bool, PasswordError> {
+    let parsed_a = parse_phc(hash_a)?;
+    let parsed_b = parse_phc(hash_b)?;
+
+    Ok(argon2id_verify_parsed(&parsed_a, password)? && argon2id_verify_parsed(&parsed_b, password)?)
+}
+
+/// Internal function to compare the decoded components of two hashes
+fn argon2id_hashes_equivalent(a: &str, b: &str) -> Result<bool, PasswordError> {
+    let a = HashParts::from_phc(a)?;
+    let b = HashParts::from_phc(b)?;
+
+    // argon2 verifies a hash without a version field as 0x13
+    let default_version = argon2::Version::default() as u32;
+    let same_header = a.algorithm == b.algorithm
+        && a.version.unwrap_or(default_version) == b.version.unwrap_or(default_version)
+        && a.params == b.params
+        && a.salt == b.salt;
+
+    let tag_a = Output::new(&a.tag).map_err(|err| PasswordError::InvalidParams(err.to_string()))?;
+    let tag_b = Output::new(&b.tag).map_err(|err| PasswordError::InvalidParams(err.to_string()))?;
+
+    // Output's equality is constant-time
+    Ok(same_header & (tag_a == tag_b))
+}
+
+/// Internal function to validate raw components and encode them as a PHC string
+fn argon2id_build_phc(salt: &[u8], tag: &[u8], options: &HashOptions) -> Result<String, PasswordError> {
+    if !(argon2::MIN_SALT_LEN..=MAX_SALT_LEN).contains(&salt.len()) {
+        return Err(PasswordError::InvalidInput(format!(
+            "Salt must be {} to {} bytes, got {}",
+            argon2::MIN_SALT_LEN,
+            MAX_SALT_LEN,
+            salt.len()
+        )));
+    }
+    if !(Output::MIN_LENGTH..=Output::MAX_LENGTH).contains(&tag.len()) {
+        return Err(PasswordError::InvalidInput(format!(
+            "Tag must be {} to {} bytes, got {}",
+            Output::MIN_LENGTH,
+            Output::MAX_LENGTH,
+            tag.len()
+        )));
+    }
+
+    let argon2 = argon2id_keyed_instance(Some(options), None, None)?;
+    let params = argon2.params();
+
+    HashParts {
+        algorithm: options.algorithm.unwrap_or(Algorithm::Argon2id).into(),
+        version: Some(argon2::Version::V0x13 as u32),
+        params: Params::new(params.m_cost(), params.t_cost(), params.p_cost(), Some(tag.len()))?,
+        salt: salt.to_vec(),
+        tag: tag.to_vec(),
+    }
+    .to_phc()
+}
+
+/// Decoded components of a PHC string
+struct HashParts {
+    algorithm: argon2::Algorithm,
+    version: Option<u32>,
+    params: Params,
+    salt: Vec<u8>,
+    tag: Vec<u8>,
+}
+
+impl HashParts {
+    /// Parses a PHC string into its decoded components
+    fn from_phc(hash: &str) -> Result<Self, PasswordError> {
+        let password_hash = parse_phc(hash)?;
+
+        let algorithm = argon2::Algorithm::try_from(password_hash.algorithm).map_err(classify_parsed_hash_error)?;
+        let params = Params::try_from(&password_hash).map_err(classify_parsed_hash_error)?;
+
+        let salt = password_hash
+            .salt
+            .ok_or_else(|| PasswordError::BadSalt("hash has no salt".to_string()))?;
+        let mut salt_buf = [0u8; Salt::MAX_LENGTH];
+        let salt = salt.decode_b64(&mut salt_buf).map_err(classify_parsed_hash_error)?.to_vec();
+
+        let tag = password_hash
+            .hash
+            .ok_or_else(|| PasswordError::BadTag("hash has no output".to_string()))?
+            .as_bytes()
+            .to_vec();
+
+        Ok(HashParts {
+            algorithm,
+            version: password_hash.version,
+            params,
+            salt,
+            tag,
+        })
+    }
+
+    /// Encodes the components back into a PHC string
+    fn to_phc(&self) -> Result<String, PasswordError> {
+        let salt = SaltString::encode_b64(&self.salt)
+            .map_err(|err| PasswordError::InvalidParams(err.to_string()))?;
+        let password_hash = PasswordHash {
+            algorithm: self.algorithm.ident(),
+            version: self.version,
+            params: ParamsString::try_from(&self.params)
+                .map_err(|err| PasswordError::InvalidParams(err.to_string()))?,
+            salt: Some(salt.as_salt()),
+            hash: Some(Output::new(&self.tag).map_err(|err| PasswordError::InvalidParams(err.to_string()))?),
+        };
+
+        Ok(password_hash.to_string())
+    }
+
+    /// Encodes the components in the hex layout documented on `hash_hex`
+    fn to_hex_format(&self) -> Result<String, PasswordError> {
+        if !self.params.keyid().is_empty() || !self.params.data().is_empty() {
+            return Err(PasswordError::InvalidInput(
+                "Hashes with keyid or data segments cannot be hex-encoded".to_string(),
+            ));
+        }
+
+        Ok(format!(
+            "${}-hex$v={}$m={},t={},p={}${}${}",
+            self.algorithm.ident(),
+            self.version.unwrap_or(argon2::Version::default() as u32),
+            self.params.m_cost(),
+            self.params.t_cost(),
+            self.params.p_cost(),
+            to_hex(&self.salt),
+            to_hex(&self.tag)
+        ))
+    }
+
+    /// Decodes the hex layout documented on `hash_hex`
+    fn from_hex_format(hash: &str) -> Result<Self, PasswordError> {
+        let invalid = || PasswordError::InvalidInput("Malformed hex hash".to_string());
+
+        let segments: Vec<&str> = hash.split('$').collect();
+        let ["", algorithm, version, params, salt, tag] = segments.as_slice() else {
+            return Err(invalid());
+        };
+
+        let algorithm = algorithm
+            .strip_suffix("-hex")
+            .and_then(|ident| ident.parse::<argon2::Algorithm>().ok())
+            .ok_or_else(invalid)?;
+        let version = version
+            .strip_prefix("v=")
+            .and_then(|version| version.parse::<u32>().ok())
+            .ok_or_else(invalid)?;
+        let options = argon2id_parse_param_string(params)?;
+        let salt = from_hex(salt).ok_or_else(invalid)?;
+        let tag = from_hex(tag).ok_or_else(invalid)?;
+
+        Ok(HashParts {
+            algorithm,
+            version: Some(version),
+            params: Params::new(options.memory_cost, options.time_cost, options.parallelism, Some(tag.len()))?,
+            salt,
+            tag,
+        })
+    }
+
+    /// Encodes the components in the layout documented on `hash_fixed_width`
+    fn to_fixed_width(&self) -> Result<Vec<u8>, PasswordError> {
+        if !self.params.keyid().is_empty() || !self.params.data().is_empty() {
+            return Err(PasswordError::InvalidInput(
+                "Hashes with keyid or data segments cannot be stored fixed-width".to_string(),
+            ));
+        }
+
+        let version = self.version.unwrap_or(argon2::Version::default() as u32);
+        let version = u8::try_from(version)
+            .map_err(|_| PasswordError::InvalidParams(format!("unsupported version {}", version)))?;
+
+        let mut record = Vec::with_capacity(FIXED_WIDTH_HEADER_LEN + self.salt.len() + self.tag.len());
+        record.push(algorithm_byte(self.algorithm));
+        record.push(version);
+        // Salts are at most 48 bytes, so the length always fits
+        record.push(self.salt.len() as u8);
+        record.extend_from_slice(&self.params.m_cost().to_be_bytes());
+        record.extend_from_slice(&self.params.t_cost().to_be_bytes());
+        record.extend_from_slice(&self.params.p_cost().to_be_bytes());
+        record.extend_from_slice(&self.salt);
+        record.extend_from_slice(&self.tag);
+        Ok(record)
+    }
+
+    /// Decodes the layout documented on `hash_fixed_width`
+    fn from_fixed_width(record: &[u8]) -> Result<Self, PasswordError> {
+        let truncated = || PasswordError::InvalidInput("Fixed-width record is truncated".to_string());
+        let (header, body) = record.split_at_checked(FIXED_WIDTH_HEADER_LEN).ok_or_else(truncated)?;
+
+        let algorithm = algorithm_from_byte(header[0])?;
+        let version = u32::from(header[1]);
+        let salt_len = usize::from(header[2]);
+        let read_u32 = |offset: usize| {
+            u32::from_be_bytes([header[offset], header[offset + 1], header[offset + 2], header[offset + 3]])
+        };
+        let (salt, tag) = body.split_at_checked(salt_len).ok_or_else(truncated)?;
+
+        Ok(HashParts {
+            algorithm,
+            version: Some(version),
+            params: Params::new(read_u32(3), read_u32(7), read_u32(11), Some(tag.len()))?,
+            salt: salt.to_vec(),
+            tag: tag.to_vec(),
+        })
+    }
+
+    /// Encodes the components in the binary layout documented on `pack_hash`
+    fn pack(&self) -> Result<Vec<u8>, PasswordError> {
+        if !self.params.keyid().is_empty() || !self.params.data().is_empty() {
+            return Err(PasswordError::InvalidInput(
+                "Hashes with keyid or data segments cannot be packed".to_string(),
+            ));
+        }
+
+        let version = match self.version {
+            None => 0,
+            Some(version) => u8::try_from(version)
+                .map_err(|_| PasswordError::InvalidParams(format!("unsupported version {}", version)))?,
+        };
+
+        let mut packed = vec![algorithm_byte(self.algorithm), version];
+        write_varint(&mut packed, u64::from(self.params.m_cost()));
+        write_varint(&mut packed, u64::from(self.params.t_cost()));
+        write_varint(&mut packed, u64::from(self.params.p_cost()));
+        write_varint(&mut packed, self.salt.len() as u64);
+        packed.extend_from_slice(&self.salt);
+        write_varint(&mut packed, self.tag.len() as u64);
+        packed.extend_from_slice(&self.tag);
+        Ok(packed)
+    }
+
+    /// Decodes the binary layout documented on `pack_hash`
+    fn unpack(bytes: &[u8]) -> Result<Self, PasswordError> {
+        let mut reader = bytes;
+
+        let algorithm = algorithm_from_byte(take(&mut reader, 1)?[0])?;
+        let version = match take(&mut reader, 1)?[0] {
+            0 => None,
+            version => Some(u32::from(version)),
+        };
+
+        let m_cost = read_varint_u32(&mut reader)?;
+        let t_cost = read_varint_u32(&mut reader)?;
+        let p_cost = read_varint_u32(&mut reader)?;
+        let salt_len = read_varint_u32(&mut reader)? as usize;
+        let salt = take(&mut reader, salt_len)?.to_vec();
+        let tag_len = read_varint_u32(&mut reader)? as usize;
+        let tag = take(&mut reader, tag_len)?.to_vec();
+
+        if !reader.is_empty() {
+            return Err(PasswordError::InvalidInput("Packed hash has trailing bytes".to_string()));
+        }
+
+        Ok(HashParts {
+            algorithm,
+            version,
+            params: Params::new(m_cost, t_cost, p_cost, Some(tag.len()))?,
+            salt,
+            tag,
+        })
+    }
+}
+
+/// Internal function to map an Argon2 variant to its packed byte
+fn algorithm_byte(algorithm: argon2::Algorithm) -> u8 {
+    match algorithm {
+        argon2::Algorithm::Argon2id => 0,
+        argon2::Algorithm::Argon2i => 1,
+        argon2::Algorithm::Argon2d => 2,
+    }
+}
+
+/// Internal function to map a packed byte back to its Argon2 variant
+fn algorithm_from_byte(byte: u8) -> Result<argon2::Algorithm, PasswordError> {
+    match byte {
+        0 => Ok(argon2::Algorithm::Argon2id),
+        1 => Ok(argon2::Algorithm::Argon2i),
+        2 => Ok(argon2::Algorithm::Argon2d),
+        other => Err(PasswordError::InvalidInput(format!("Unknown packed algorithm byte {}", other))),
+    }
+}
+
+/// Length of the fixed-width record header: algorithm, version, salt length, m, t, p
+const FIXED_WIDTH_HEADER_LEN: usize = 3 + 3 * 4;
+
+/// Internal function to append an unsigned LEB128 varint
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Internal function to read an unsigned LEB128 varint that must fit in a u32
+fn read_varint_u32(reader: &mut &[u8]) -> Result<u32, PasswordError> {
+    let mut value: u64 = 0;
+    for shift in (0..35).step_by(7) {
+        let byte = take(reader, 1)?[0];
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return u32::try_from(value)
+                .map_err(|_| PasswordError::InvalidInput("Packed varint out of range".to_string()));
+        }
+    }
+    Err(PasswordError::InvalidInput("Packed varint is too long".to_string()))
+}
+
+/// Internal function to split `len` bytes off the front of a packed record
+fn take<'a>(reader: &mut &'a [u8], len: usize) -> Result<&'a [u8], PasswordError> {
+    if reader.len() < len {
+        return Err(PasswordError::InvalidInput("Packed hash is truncated".to_string()));
+    }
+    let (head, tail) = reader.split_at(len);
+    *reader = tail;
+    Ok(head)
+}
+
+/// Internal function to compute the parameter fingerprint of a hash
+fn argon2id_param_fingerprint(hash: &str) -> Result<String, PasswordError> {
+    let password_hash = parse_phc(hash)?;
+    let params = Params::try_from(&password_hash).map_err(classify_parsed_hash_error)?;
+
+    // A missing version is read as the current one, matching verification
+    let version = password_hash.version.unwrap_or(argon2::Version::V0x13 as u32);
+    let canonical = format!(
+        "{}|{}|{}|{}|{}",
+        password_hash.algorithm,
+        version,
+        params.m_cost(),
+        params.t_cost(),
+        params.p_cost()
+    );
+
+    let digest = Blake2b512::digest(canonical.as_bytes());
+    Ok(to_hex(&digest[..8]))
+}
+
+/// Internal function to encode bytes as lowercase hex
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Internal function to decode hex, returning `None` on any non-hex character
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Internal function to decide the `login_check` action for an already-verified hash
+fn argon2id_login_action(hash: &str, policy: &LoginPolicy) -> Result<&'static str, PasswordError> {
+    let parts = HashParts::from_phc(hash)?;
+    let params = &parts.params;
+
+    let below_floor = policy.min_memory_cost.is_some_and(|min| params.m_cost() < min)
+        || policy.min_time_cost.is_some_and(|min| params.t_cost() < min);
+
+    let expired = match (policy.max_age_days, params.data().len()) {
+        (Some(max_age_days), 8) => {
+            let now_unix = policy.now_unix.ok_or_else(|| {
+                PasswordError::InvalidInput("Policy max_age_days requires now_unix".to_string())
+            })?;
+            (now_unix - argon2id_timestamp(hash)? as f64) / SECONDS_PER_DAY > max_age_days
+        }
+        _ => false,
+    };
+
+    if below_floor || expired {
+        return Ok("reset");
+    }
+
+    let outdated = policy.target.as_ref().is_some_and(|target| {
+        argon2::Algorithm::from(target.algorithm.unwrap_or(Algorithm::Argon2id)) != parts.algorithm
+            || target.memory_cost != params.m_cost()
+            || target.time_cost != params.t_cost()
+            || target.parallelism != params.p_cost()
+            || target.epoch.is_some_and(|epoch| epoch_from_params(params).unwrap_or(0) < epoch)
+    });
+
+    Ok(if outdated { "rehash" } else { "ok" })
+}
+
+/// Internal function to read the rotation epoch from a hash's `data` segment, if it has one
+fn epoch_from_params(params: &Params) -> Option<u32> {
+    params.data().try_into().ok().map(u32::from_be_bytes)
+}
+
+/// Internal function to read the creation timestamp from a hash's `data` segment
+fn argon2id_timestamp(hash: &str) -> Result<u64, PasswordError> {
+    let params = argon2id_params(hash)?;
+
+    let timestamp: [u8; 8] = params.data().try_into().map_err(|_| {
+        PasswordError::InvalidInput("Hash does not carry a creation timestamp".to_string())
+    })?;
+    Ok(u64::from_be_bytes(timestamp))
+}
+
+/// Hash time `min_safe_params` aims for, in milliseconds
+const TARGET_HASH_MS: f64 = 250.0;
+/// Memory cost bounds for `min_safe_params` recommendations, in KiB
+const MIN_RECOMMENDED_MEMORY_COST: u32 = 8 * 1024;
+const MAX_RECOMMENDED_MEMORY_COST: u32 = 1024 * 1024;
+
+/// Upper bound on the `time_cost` chosen by `hash_adaptive`
+const MAX_ADAPTIVE_TIME_COST: u32 = 16;
+
+/// Internal function to time one pass and hash with as many passes as fit the target
+fn argon2id_hash_adaptive(
+    password: &[u8],
+    target_ms: f64,
+    mut options: HashOptions,
+) -> Result<AdaptiveHash, PasswordError> {
+    if !target_ms.is_finite() || target_ms <= 0.0 {
+        return Err(PasswordError::InvalidInput(
+            "Target time must be a positive number of milliseconds".to_string(),
+        ));
+    }
+
+    let probe = HashOptions {
+        time_cost: 1,
+        ..options.clone()
+    };
+    let argon2 = argon2id_keyed_instance(Some(&probe), None, None)?;
+    let per_pass_ms = {
+        let params = argon2.params();
+        let _reservation = MemoryReservation::acquire(estimated_memory_bytes(params.m_cost()))?;
+        let mut blocks = allocate_blocks(params)?;
+        let mut scratch = vec![0u8; params.output_len().unwrap_or(Params::DEFAULT_OUTPUT_LEN)];
+        let start = now_ms();
+        argon2.hash_password_into_with_memory(
+            b"hash_adaptive probe",
+            b"hash_adaptive salt",
+            &mut scratch,
+            &mut blocks,
+        )?;
+        now_ms() - start
+    };
+
+    // A probe below the timer's resolution means passes are cheap here
+    options.time_cost = if per_pass_ms > 0.0 {
+        ((target_ms / per_pass_ms).round() as u32).clamp(1, MAX_ADAPTIVE_TIME_COST)
+    } else {
+        MAX_ADAPTIVE_TIME_COST
+    };
+
+    let hash = argon2id_hash(password, Some(options.clone()))?;
+    Ok(AdaptiveHash {
+        hash,
+        options_used: options,
+    })
+}
+
+/// Internal function to time one hash under each of two param sets
+fn argon2id_latency_delta(old_options: HashOptions, new_options: HashOptions) -> Result<LatencyDelta, PasswordError> {
+    let time_hash = |options: HashOptions| -> Result<f64, PasswordError> {
+        let start = now_ms();
+        argon2id_hash(b"latency_delta probe", Some(options))?;
+        Ok(now_ms() - start)
+    };
+
+    let old_ms = time_hash(old_options)?;
+    let new_ms = time_hash(new_options)?;
+
+    // A hash faster than the timer's resolution reads as 0 ms
+    let factor = if old_ms > 0.0 { new_ms / old_ms } else { f64::INFINITY };
+
+    Ok(LatencyDelta { old_ms, new_ms, factor })
+}
+
+/// Internal function to count how many hashes complete within a wall-clock window
+fn argon2id_throughput(options: HashOptions, duration_ms: u32) -> Result<f64, PasswordError> {
+    if duration_ms == 0 {
+        return Err(PasswordError::InvalidInput("Duration must be at least 1 millisecond".to_string()));
+    }
+
+    let start = now_ms();
+    let mut count = 0u32;
+    let elapsed_ms = loop {
+        argon2id_hash(b"throughput probe", Some(options.clone()))?;
+        count += 1;
+
+        let elapsed_ms = now_ms() - start;
+        if elapsed_ms >= f64::from(duration_ms) {
+            break elapsed_ms;
+        }
+    };
+
+    Ok(f64::from(count) * 1000.0 / elapsed_ms)
+}
+
+/// Internal function to scale the default memory cost to a device's measured speed
+fn argon2id_min_safe_params(measured_ms: f64) -> Result<ParamRecommendation, PasswordError> {
+    if !measured_ms.is_finite() || measured_ms <= 0.0 {
+        return Err(PasswordError::InvalidInput(
+            "Measured hash time must be a positive number of milliseconds".to_string(),
+        ));
+    }
+
+    let scaled = f64::from(Params::DEFAULT_M_COST) * TARGET_HASH_MS / measured_ms;
+    let memory_cost = ((scaled as u32) / 1024 * 1024)
+        .clamp(MIN_RECOMMENDED_MEMORY_COST, MAX_RECOMMENDED_MEMORY_COST);
+
+    Ok(ParamRecommendation {
+        time_cost: Params::DEFAULT_T_COST,
+        memory_cost,
+        parallelism: Params::DEFAULT_P_COST,
+        estimated_ms: measured_ms * f64::from(memory_cost) / f64::from(Params::DEFAULT_M_COST),
+    })
+}
+
+/// Known-answer vectors for `self_test`, produced by the reference argon2 implementation
+const SELF_TEST_PASSWORD: &str = "password";
+const SELF_TEST_SALT: &str = "c29tZXNhbHQ";
+const SELF_TEST_HASH: &str = "$argon2id$v=19$m=64,t=1,p=1$c29tZXNhbHQ$cpx6VEQbwTVZvcpxNIxOVUWZ5xnAipUmAe1cg2GMG70";
+const SELF_TEST_VERIFY_HASH: &str = "$argon2id$v=19$m=32,t=2,p=2$c29tZXNhbHQ$o5MEdG+tAx306RGn9RszCBEgOR80JQb3DwzoJBEvpWE";
+
+/// Internal function to run the known-answer self-test
+fn argon2id_self_test() -> Result<bool, PasswordError> {
+    let salt = SaltString::from_b64(SELF_TEST_SALT)
+        .map_err(|err| PasswordError::InvalidParams(err.to_string()))?;
+    let options = HashOptions {
+        time_cost: 1,
+        memory_cost: 64,
+        parallelism: 1,
+        ..Default::default()
+    };
+
+    let hash = argon2id_hash_with_salt(SELF_TEST_PASSWORD.as_bytes(), None, None, &salt, Some(options))?;
+    let verified = argon2id_verify_bytes(SELF_TEST_VERIFY_HASH, SELF_TEST_PASSWORD.as_bytes())?;
+
+    Ok(hash == SELF_TEST_HASH && verified)
+}
+
+/// Internal function to split a PHC string into its public part and the tag
+fn split_verifier(hash: &str) -> Result<SplitStorageHash, PasswordError> {
+    let (public_params, verifier_b64) = hash
+        .rsplit_once('$')
+        .ok_or_else(|| PasswordError::InvalidParams("hash has no output segment".to_string()))?;
+
+    Ok(SplitStorageHash {
+        public_params: public_params.to_string(),
+        verifier_b64: verifier_b64.to_string(),
+    })
+}
+
+/// Internal function to parse the Argon2 parameters out of a PHC string
+fn argon2id_params(hash: &str) -> Result<Params, PasswordError> {
+    let password_hash = parse_phc(hash)?;
+
+    Params::try_from(&password_hash).map_err(classify_parsed_hash_error)
+}
+
+/// Internal function to estimate the work of one hash computation in MiB-passes
+fn argon2id_compute_units(params: &Params) -> f64 {
+    f64::from(params.m_cost()) * f64::from(params.t_cost()) / 1024.0
+}
+
+/// Internal function to estimate log2 of the block operations a hash costs
+fn argon2id_work_bits(params: &Params) -> f64 {
+    (f64::from(params.m_cost()) * f64::from(params.t_cost())).log2()
+}
+
+/// Internal function to compare the parameter strength of two hashes
+fn argon2id_compare_strength(old_hash: &str, new_hash: &str) -> Result<StrengthComparison, PasswordError> {
+    let old = argon2id_params(old_hash)?;
+    let new = argon2id_params(new_hash)?;
+
+    let old_work = u64::from(old.m_cost()) * u64::from(old.t_cost());
+    let new_work = u64::from(new.m_cost()) * u64::from(new.t_cost());
+
+    let stronger = match new_work.cmp(&old_work) {
+        std::cmp::Ordering::Greater => "new",
+        std::cmp::Ordering::Less => "old",
+        std::cmp::Ordering::Equal => "equal",
+    };
+
+    Ok(StrengthComparison {
+        stronger: stronger.to_string(),
+        memory_delta: i64::from(new.m_cost()) - i64::from(old.m_cost()),
+        time_delta: i64::from(new.t_cost()) - i64::from(old.t_cost()),
+    })
+}
+
+/// Inputs for `generate_vectors`: password, base64 salt, algorithm, m, t, p
+#[cfg(feature = "test-vectors")]
+const TEST_VECTOR_INPUTS: [(&str, &str, Algorithm, u32, u32, u32); 4] = [
+    ("password", "c29tZXNhbHQ", Algorithm::Argon2id, 16, 1, 1),
+    ("correct horse battery staple", "YW5vdGhlcnNhbHQ", Algorithm::Argon2id, 32, 2, 1),
+    ("p\u{e4}ssw\u{f6}rd", "dW5pY29kZXNhbHQ", Algorithm::Argon2id, 64, 1, 2),
+    ("password", "c29tZXNhbHQ", Algorithm::Argon2i, 16, 2, 1),
+];
+
+/// Internal function to compute the fixed test vectors
+#[cfg(feature = "test-vectors")]
+fn argon2id_generate_vectors() -> Result<Vec<TestVector>, PasswordError> {
+    TEST_VECTOR_INPUTS
+        .iter()
+        .map(|&(password, salt, algorithm, memory_cost, time_cost, parallelism)| {
+            let salt = SaltString::from_b64(salt).map_err(|err| PasswordError::InvalidParams(err.to_string()))?;
+            let options = HashOptions {
+                time_cost,
+                memory_cost,
+                parallelism,
+                algorithm: Some(algorithm),
+                ..Default::default()
+            };
+            let hash = argon2id_hash_with_salt(password.as_bytes(), None, None, &salt, Some(options.clone()))?;
+
+            Ok(TestVector {
+                password: password.to_string(),
+                options,
+                hash,
+            })
         })
+        .collect()
 }
@@ -1,7 +1,36 @@
-use argon2id_wasm::{hash, verify, HashOptions};
-use wasm_bindgen::JsValue;
+#![cfg(target_arch = "wasm32")]
+
+use argon2id_wasm::{
+    audit_homogeneity, blind_index, build_phc, can_resalt, check_verifier, clear_event_sink,
+    clear_memory_budget, compare_strength, cost_breakdown, current_epoch, derive_key_for,
+    derive_key_jwk, derive_keys, effective_password, format_memory, format_param_string,
+    has_secure_rng, hash, hash_adaptive, hash_age_days, hash_authenticated_params,
+    hash_batch_fixed_salt, hash_field_presence, hash_fields, hash_fixed_width, hash_hex,
+    hash_into_object, hash_namespaced, hash_prehashed, hash_prepended_pepper, hash_reference_format,
+    hash_split_storage, hash_timestamped, hash_with_policy, hash_with_transform, hashes_equivalent,
+    is_native_hash, latency_delta, login_check, mac, mac_verify, make_verifier, min_safe_params,
+    needs_epoch_rehash, needs_rehash_against_presets, options_json_schema, pack_hash, pack_params,
+    param_bounds, param_fingerprint, param_histogram, parse_memory, parse_param_string, prewarm,
+    register_preset, resalt, reset_state, self_test, set_event_sink, set_memory_budget, throughput,
+    try_verify, unpack_hash, unpack_params, unwrap_credential, verify, verify_authenticated_params,
+    verify_batch_timed, verify_bounded, verify_cached, verify_derived, verify_encodings,
+    verify_fields, verify_fixed_width, verify_fresh, verify_hash_bytes, verify_hex, verify_identify,
+    verify_legacy_concat, verify_lenient, verify_metered, verify_min_strength,
+    verify_not_compromised, verify_packed, verify_prehashed, verify_prepended_pepper,
+    verify_reference_format, verify_request, verify_split_storage, verify_streaming, verify_timed,
+    verify_versioned, verify_with_deadline, verify_with_external_salt, verify_with_options,
+    verify_with_param_override, verify_with_transform, work_bits, would_both_accept,
+    wrap_credential, AdaptiveHash, Algorithm, BoundedVerification, CostBreakdown,
+    DeadlineVerification, DenylistVerification, DiagnosticEvent, EncodingMatch, FieldPresence,
+    FreshVerification, HashOptions, HomogeneityAudit, IdentifiedVerification, Jwk, LatencyDelta,
+    LoginDecision, LoginPolicy, MeteredVerification, ParamBounds, ParamHistogram, ParamOverride,
+    ParamRecommendation, PasswordError, PasswordPolicy, PolicyVerification, SplitStorageHash,
+    StrengthComparison, TimedBatchVerification, TimedVerification, TryVerifyResult, VerifyOptions,
+    VerifyRequest, VersionedVerification,
+};
+use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_test::*;
-use js_sys::Object;
+use js_sys::{Object, Uint8Array};
 
 #[wasm_bindgen_test]
 fn test_hash_with_empty_password() {
@@ -32,6 +61,7 @@ fn test_hash_with_custom_options() {
         time_cost: 2,
         memory_cost: 16,
         parallelism: 1,
+        ..Default::default()
     };
     let js_options = serde_wasm_bindgen::to_value(&options).unwrap();
     let result = hash("test123", js_options);
@@ -44,6 +74,7 @@ fn test_hash_with_invalid_options() {
         time_cost: 0, // Invalid time_cost
         memory_cost: 8,
         parallelism: 1,
+        ..Default::default()
     };
     let js_options = serde_wasm_bindgen::to_value(&options).unwrap();
     let result = hash("test123", js_options);
@@ -87,3 +118,2325 @@ fn test_verify_password_flow() {
     assert!(result.is_ok());
     assert!(!result.unwrap());
 }
+
+fn hash_with(time_cost: u32, memory_cost: u32) -> String {
+    let options = HashOptions {
+        time_cost,
+        memory_cost,
+        parallelism: 1,
+        ..Default::default()
+    };
+    let js_options = serde_wasm_bindgen::to_value(&options).unwrap();
+    hash("test123", js_options).unwrap()
+}
+
+/// Median of several wall-clock samples, so one slow run cannot flip a comparison
+fn median(mut samples: Vec<f64>) -> f64 {
+    samples.sort_by(f64::total_cmp);
+    samples[samples.len() / 2]
+}
+
+#[wasm_bindgen_test]
+fn test_compare_strength_upgrade() {
+    let old = hash_with(1, 16);
+    let new = hash_with(2, 32);
+
+    let result: StrengthComparison =
+        serde_wasm_bindgen::from_value(compare_strength(&old, &new).unwrap()).unwrap();
+    assert_eq!(result.stronger, "new");
+    assert_eq!(result.memory_delta, 16);
+    assert_eq!(result.time_delta, 1);
+}
+
+#[wasm_bindgen_test]
+fn test_compare_strength_downgrade() {
+    let old = hash_with(3, 64);
+    let new = hash_with(1, 16);
+
+    let result: StrengthComparison =
+        serde_wasm_bindgen::from_value(compare_strength(&old, &new).unwrap()).unwrap();
+    assert_eq!(result.stronger, "old");
+    assert_eq!(result.memory_delta, -48);
+    assert_eq!(result.time_delta, -2);
+}
+
+#[wasm_bindgen_test]
+fn test_compare_strength_equal() {
+    let old = hash_with(2, 16);
+    let new = hash_with(2, 16);
+
+    let result: StrengthComparison =
+        serde_wasm_bindgen::from_value(compare_strength(&old, &new).unwrap()).unwrap();
+    assert_eq!(result.stronger, "equal");
+    assert_eq!(result.memory_delta, 0);
+    assert_eq!(result.time_delta, 0);
+}
+
+fn error_message(err: JsValue) -> String {
+    let obj: Object = err.into();
+    js_sys::Reflect::get(&obj, &JsValue::from_str("message"))
+        .unwrap()
+        .as_string()
+        .unwrap()
+}
+
+#[wasm_bindgen_test]
+fn test_prehashed_password_flow() {
+    // Stand-in for a client-side HMAC-SHA256 of the password
+    let digest: Vec<u8> = (0u8..32).map(|i| i.wrapping_mul(37) ^ 0x5a).collect();
+
+    let hash = hash_prehashed(&digest, JsValue::NULL).unwrap();
+    assert!(hash.starts_with("$argon2id$"));
+
+    assert!(verify_prehashed(&hash, &digest).unwrap());
+
+    let mut tampered = digest.clone();
+    tampered[0] ^= 1;
+    assert!(!verify_prehashed(&hash, &tampered).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_hash_prehashed_rejects_wrong_digest_size() {
+    let result = hash_prehashed(b"not-a-digest", JsValue::NULL);
+    assert!(result.is_err());
+    assert_eq!(
+        error_message(result.unwrap_err()),
+        "Invalid password or hash: Pre-hashed digest must be 32 or 64 bytes, got 12"
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_derive_key_for_aes256() {
+    let key = derive_key_for(b"correct horse", b"somesalt", "aes256", JsValue::NULL).unwrap();
+    assert_eq!(key.length(), 32);
+
+    let again = derive_key_for(b"correct horse", b"somesalt", "aes256", JsValue::NULL).unwrap();
+    assert_eq!(key.to_vec(), again.to_vec());
+}
+
+#[wasm_bindgen_test]
+fn test_derive_key_for_unknown_algorithm() {
+    let result = derive_key_for(b"correct horse", b"somesalt", "des", JsValue::NULL);
+    assert!(result.is_err());
+    assert_eq!(
+        error_message(result.unwrap_err()),
+        "Invalid password or hash: Unknown key algorithm: des"
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_resalt_is_rejected() {
+    assert!(!can_resalt());
+
+    let hash = hash_with(1, 16);
+    let result = resalt(&hash);
+    assert!(result.is_err());
+    assert_eq!(
+        error_message(result.unwrap_err()),
+        "Invalid password or hash: cannot re-salt without the plaintext password; re-hash on next login instead"
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_verify_timed_reports_elapsed() {
+    let cheap = hash_with(1, 16);
+    let expensive = hash_with(4, 8192);
+
+    let timed = |hash: &str| -> Vec<f64> {
+        (0..3)
+            .map(|_| {
+                let result: TimedVerification =
+                    serde_wasm_bindgen::from_value(verify_timed(hash, "test123").unwrap()).unwrap();
+                assert!(result.valid);
+                assert!(result.elapsed_ms.is_finite() && result.elapsed_ms >= 0.0);
+                result.elapsed_ms
+            })
+            .collect()
+    };
+
+    // The expensive hash costs 2048x the cheap one, far beyond timer noise
+    assert!(median(timed(&expensive)) > median(timed(&cheap)));
+}
+
+#[wasm_bindgen_test]
+fn test_padded_passwords_in_same_bucket() {
+    let options = HashOptions {
+        time_cost: 2,
+        memory_cost: 4096,
+        parallelism: 1,
+        pad_password_to: Some(64),
+        ..Default::default()
+    };
+    let verify_options = VerifyOptions {
+        pad_password_to: Some(64),
+        ..Default::default()
+    };
+
+    let short = hash("abc", serde_wasm_bindgen::to_value(&options).unwrap()).unwrap();
+    let long = hash(&"x".repeat(50), serde_wasm_bindgen::to_value(&options).unwrap()).unwrap();
+
+    // Both inputs pad to 64 bytes, so Argon2 receives the same input length
+    let padded_len = |password: &str| {
+        effective_password(password, serde_wasm_bindgen::to_value(&options).unwrap()).unwrap().to_vec().len()
+    };
+    assert_eq!(padded_len("abc"), 64);
+    assert_eq!(padded_len(&"x".repeat(50)), 64);
+
+    let js_verify_options = serde_wasm_bindgen::to_value(&verify_options).unwrap();
+    assert!(verify_with_options(&short, "abc", js_verify_options.clone()).unwrap());
+    assert!(verify_with_options(&long, &"x".repeat(50), js_verify_options.clone()).unwrap());
+    assert!(!verify_with_options(&short, "abd", js_verify_options).unwrap());
+
+    // Without the matching bucket size the padded hash does not verify
+    assert!(!verify(&short, "abc").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_would_both_accept() {
+    let hash_a = hash_with(1, 16);
+    let hash_b = hash_with(2, 32);
+    assert_ne!(hash_a, hash_b);
+
+    assert!(would_both_accept(&hash_a, &hash_b, "test123").unwrap());
+    assert!(!would_both_accept(&hash_a, &hash_b, "wrong_password").unwrap());
+    assert!(would_both_accept(&hash_a, "invalid_hash", "test123").is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_hash_with_algorithm_enum() {
+    let options = HashOptions {
+        time_cost: 1,
+        memory_cost: 16,
+        parallelism: 1,
+        algorithm: Some(Algorithm::Argon2i),
+        ..Default::default()
+    };
+    let hash = hash("test123", serde_wasm_bindgen::to_value(&options).unwrap()).unwrap();
+    assert!(hash.starts_with("$argon2i$"));
+    assert!(verify(&hash, "test123").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_hash_with_algorithm_string() {
+    let options = Object::new();
+    js_sys::Reflect::set(&options, &"time_cost".into(), &1.into()).unwrap();
+    js_sys::Reflect::set(&options, &"memory_cost".into(), &16.into()).unwrap();
+    js_sys::Reflect::set(&options, &"parallelism".into(), &1.into()).unwrap();
+    js_sys::Reflect::set(&options, &"algorithm".into(), &"argon2d".into()).unwrap();
+
+    let hash = hash("test123", options.into()).unwrap();
+    assert!(hash.starts_with("$argon2d$"));
+}
+
+#[wasm_bindgen_test]
+fn test_memory_budget() {
+    set_memory_budget(64.0 * 1024.0).unwrap();
+
+    let result = hash("test123", serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 4096,
+        parallelism: 1,
+        ..Default::default()
+    }).unwrap());
+    let small = hash("test123", serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 16,
+        parallelism: 1,
+        ..Default::default()
+    }).unwrap());
+
+    clear_memory_budget();
+
+    assert!(result.is_err());
+    assert!(error_message(result.unwrap_err()).starts_with("Resource exhausted:"));
+    assert!(small.is_ok());
+
+    // With the limiter cleared the large hash goes through
+    assert!(hash_with(1, 4096).starts_with("$argon2id$"));
+}
+
+#[wasm_bindgen_test]
+fn test_verify_with_reordered_params() {
+    // argon2 looks params up by name, so a t,m,p ordering parses without normalization
+    let hash = hash_with(2, 16);
+    let reordered = hash.replace("m=16,t=2,p=1", "t=2,m=16,p=1");
+    assert_ne!(hash, reordered);
+
+    assert!(verify(&reordered, "test123").unwrap());
+    assert!(!verify(&reordered, "wrong_password").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_split_storage_round_trip() {
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 16,
+        parallelism: 1,
+        ..Default::default()
+    })
+    .unwrap();
+    let split: SplitStorageHash = serde_wasm_bindgen::from_value(
+        hash_split_storage("test123", b"server-secret", options).unwrap(),
+    )
+    .unwrap();
+    assert!(split.public_params.starts_with("$argon2id$"));
+    assert!(!split.verifier_b64.contains('$'));
+
+    let (params, verifier) = (&split.public_params, &split.verifier_b64);
+    assert!(verify_split_storage(params, verifier, "test123", b"server-secret").unwrap());
+    assert!(!verify_split_storage(params, verifier, "test123", b"other-secret").unwrap());
+    assert!(!verify_split_storage(params, verifier, "wrong_password", b"server-secret").unwrap());
+
+    // Another credential's verifier does not match these params
+    let other: SplitStorageHash = serde_wasm_bindgen::from_value(
+        hash_split_storage("test123", b"server-secret", JsValue::NULL).unwrap(),
+    )
+    .unwrap();
+    assert!(!verify_split_storage(params, &other.verifier_b64, "test123", b"server-secret").unwrap());
+
+    // The joined string alone is useless without the secret
+    let joined = format!("{}${}", split.public_params, split.verifier_b64);
+    assert!(!verify(&joined, "test123").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_self_test() {
+    assert!(self_test().unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_verify_with_transform() {
+    let uppercase = js_sys::Function::new_with_args("p", "return p.toUpperCase()");
+
+    let upper_hash = hash("ABC", JsValue::NULL).unwrap();
+    assert!(verify_with_transform(&upper_hash, "abc", &uppercase).unwrap());
+    assert!(!verify_with_transform(&upper_hash, "abd", &uppercase).unwrap());
+    assert!(!verify(&upper_hash, "abc").unwrap());
+
+    let transformed_hash = hash_with_transform("abc", &uppercase, JsValue::NULL).unwrap();
+    assert!(verify(&transformed_hash, "ABC").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_transform_must_return_string() {
+    let broken = js_sys::Function::new_with_args("p", "return 42");
+
+    let result = hash_with_transform("abc", &broken, JsValue::NULL);
+    assert!(result.is_err());
+    assert_eq!(
+        error_message(result.unwrap_err()),
+        "Invalid password or hash: Password transform must return a string"
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_min_safe_params_scales_with_device_speed() {
+    let fast: ParamRecommendation =
+        serde_wasm_bindgen::from_value(min_safe_params(25.0).unwrap()).unwrap();
+    let slow: ParamRecommendation =
+        serde_wasm_bindgen::from_value(min_safe_params(200.0).unwrap()).unwrap();
+
+    assert!(slow.memory_cost < fast.memory_cost);
+    assert!(fast.estimated_ms <= 250.0);
+    assert!(slow.estimated_ms <= 250.0);
+    assert_eq!(fast.memory_cost % 1024, 0);
+
+    assert!(min_safe_params(0.0).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_try_verify_match_and_mismatch() {
+    let hash = hash_with(1, 16);
+
+    let matched: TryVerifyResult = serde_wasm_bindgen::from_value(try_verify(&hash, "test123")).unwrap();
+    assert!(matched.ok);
+    assert!(matched.valid);
+    assert!(matched.error.is_none());
+
+    let mismatched: TryVerifyResult =
+        serde_wasm_bindgen::from_value(try_verify(&hash, "wrong_password")).unwrap();
+    assert!(mismatched.ok);
+    assert!(!mismatched.valid);
+    assert!(mismatched.error.is_none());
+}
+
+#[wasm_bindgen_test]
+fn test_try_verify_accepts_normalized_forms() {
+    let hashed = hash_with(1, 16);
+    let hex = hash_hex("test123", JsValue::NULL).unwrap();
+
+    for form in [format!("{}\r\n", hashed), wrap_credential(&hashed), hex] {
+        let result: TryVerifyResult = serde_wasm_bindgen::from_value(try_verify(&form, "test123")).unwrap();
+        assert!(result.ok);
+        assert!(result.valid);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_try_verify_malformed_hash() {
+    let result: TryVerifyResult =
+        serde_wasm_bindgen::from_value(try_verify("invalid_hash", "test123")).unwrap();
+    assert!(!result.ok);
+    assert!(!result.valid);
+
+    let error = result.error.unwrap();
+    assert_eq!(error.code, "INVALID_PARAMS");
+    assert!(error.message.contains("Invalid hash parameters"));
+}
+
+#[wasm_bindgen_test]
+fn test_error_object_has_code() {
+    let err = hash("", JsValue::NULL).unwrap_err();
+    let code = js_sys::Reflect::get(&err, &JsValue::from_str("code"))
+        .unwrap()
+        .as_string()
+        .unwrap();
+    assert_eq!(code, "INVALID_INPUT");
+}
+
+#[wasm_bindgen_test]
+fn test_hash_timestamped_age() {
+    let created = 1_700_000_000.0;
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 16,
+        parallelism: 1,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let hash = hash_timestamped("test123", options, created).unwrap();
+    assert!(hash.contains(",data="));
+    assert!(verify(&hash, "test123").unwrap());
+    assert!(!verify(&hash, "wrong_password").unwrap());
+
+    let age = hash_age_days(&hash, created + 30.0 * 86_400.0).unwrap();
+    assert!((age - 30.0).abs() < 1e-9);
+
+    // A hash without a timestamp has no age
+    assert!(hash_age_days(&hash_with(1, 16), created).is_err());
+}
+
+fn verify_requests(items: &[(&str, &str)]) -> JsValue {
+    let requests: Vec<VerifyRequest> = items
+        .iter()
+        .map(|(hash, password)| VerifyRequest {
+            hash: hash.to_string(),
+            password: password.to_string(),
+        })
+        .collect();
+    serde_wasm_bindgen::to_value(&requests).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn test_verify_batch_timed() {
+    let cheap = hash_with(1, 16);
+    let expensive = hash_with(2, 4096);
+
+    let requests = verify_requests(&[
+        (&cheap, "test123"),
+        (&expensive, "test123"),
+        (&cheap, "wrong_password"),
+    ]);
+    let batch: TimedBatchVerification =
+        serde_wasm_bindgen::from_value(verify_batch_timed(requests).unwrap()).unwrap();
+
+    assert_eq!(batch.results, vec![true, true, false]);
+    assert_eq!(batch.per_item_ms.len(), 3);
+
+    assert!(batch.per_item_ms.iter().all(|ms| ms.is_finite() && *ms >= 0.0));
+
+    // Each item is timed inside the batch window, up to 1 ms of rounding apiece
+    let sum: f64 = batch.per_item_ms.iter().sum();
+    assert!(sum <= batch.total_ms + batch.per_item_ms.len() as f64);
+}
+
+#[wasm_bindgen_test]
+fn test_verify_batch_timed_reports_malformed_index() {
+    let valid = hash_with(1, 16);
+    let requests = verify_requests(&[(&valid, "test123"), ("invalid_hash", "test123")]);
+
+    let result = verify_batch_timed(requests);
+    assert!(result.is_err());
+    assert!(error_message(result.unwrap_err()).starts_with("Invalid hash parameters: entry 1:"));
+}
+
+#[wasm_bindgen_test]
+fn test_verify_batch_timed_indexes_classified_errors() {
+    let valid = hash_with(1, 16);
+    let mut segments: Vec<&str> = valid.split('$').collect();
+    segments[4] = "c2FsdA!!";
+    let bad_salt = segments.join("$");
+    let scrypt = "$scrypt$ln=16,r=8,p=1$aM15713r3Xsvxbi31lqr1Q$nFNh2CVHVjNldFVKDHDlm4CbdRSCdEBsjjJxD+iCs5E";
+
+    let err = verify_batch_timed(verify_requests(&[(&valid, "test123"), (&bad_salt, "test123")])).unwrap_err();
+    let code = js_sys::Reflect::get(&err, &JsValue::from_str("code")).unwrap();
+    assert_eq!(code.as_string().unwrap(), "BAD_SALT");
+    assert!(error_message(err).starts_with("Malformed hash salt: entry 1:"));
+
+    let err = verify_batch_timed(verify_requests(&[(scrypt, "test123")])).unwrap_err();
+    assert!(error_message(err).starts_with("Unsupported hash algorithm: entry 0: scrypt"));
+}
+
+fn derived_key(keys: &JsValue, label: &str) -> Vec<u8> {
+    js_sys::Reflect::get(keys, &JsValue::from_str(label))
+        .unwrap()
+        .dyn_into::<js_sys::Uint8Array>()
+        .unwrap()
+        .to_vec()
+}
+
+#[wasm_bindgen_test]
+fn test_derive_keys_domain_separation() {
+    let labels = vec!["encryption".to_string(), "mac".to_string()];
+    let keys = derive_keys(b"correct horse", b"somesalt", labels.clone(), 32, JsValue::NULL).unwrap();
+
+    let encryption = derived_key(&keys, "encryption");
+    let mac = derived_key(&keys, "mac");
+    assert_eq!(encryption.len(), 32);
+    assert_ne!(encryption, mac);
+
+    let again = derive_keys(b"correct horse", b"somesalt", labels, 32, JsValue::NULL).unwrap();
+    assert_eq!(derived_key(&again, "encryption"), encryption);
+}
+
+#[wasm_bindgen_test]
+fn test_verify_not_compromised() {
+    let leaked = hash_with(1, 16);
+    let fresh = hash_with(1, 16);
+    let denylist = vec![leaked.clone()];
+
+    let result: DenylistVerification = serde_wasm_bindgen::from_value(
+        verify_not_compromised(&leaked, "test123", denylist.clone()).unwrap(),
+    )
+    .unwrap();
+    assert!(!result.valid);
+    assert!(result.compromised);
+
+    let result: DenylistVerification = serde_wasm_bindgen::from_value(
+        verify_not_compromised(&fresh, "test123", denylist).unwrap(),
+    )
+    .unwrap();
+    assert!(result.valid);
+    assert!(!result.compromised);
+}
+
+#[wasm_bindgen_test]
+fn test_verify_not_compromised_normalizes_entries() {
+    let leaked = hash_with(1, 16);
+    let denylist = vec!["not a hash".to_string(), format!("{}\n", wrap_credential(&leaked))];
+
+    let result: DenylistVerification = serde_wasm_bindgen::from_value(
+        verify_not_compromised(&format!("  {}", leaked), "test123", denylist).unwrap(),
+    )
+    .unwrap();
+    assert!(!result.valid);
+    assert!(result.compromised);
+}
+
+#[wasm_bindgen_test]
+fn test_allow_empty_password() {
+    let options = HashOptions {
+        time_cost: 1,
+        memory_cost: 16,
+        parallelism: 1,
+        allow_empty: true,
+        ..Default::default()
+    };
+    let empty_hash = hash("", serde_wasm_bindgen::to_value(&options).unwrap()).unwrap();
+
+    let verify_options = VerifyOptions {
+        allow_empty: true,
+        ..Default::default()
+    };
+    let js_verify_options = serde_wasm_bindgen::to_value(&verify_options).unwrap();
+    assert!(verify_with_options(&empty_hash, "", js_verify_options.clone()).unwrap());
+    assert!(!verify_with_options(&empty_hash, "guest", js_verify_options).unwrap());
+
+    // Without the flag, empty passwords are still rejected on both sides
+    assert!(verify(&empty_hash, "").is_err());
+    assert!(verify_with_options(&empty_hash, "", JsValue::NULL).is_err());
+    let strict = HashOptions {
+        allow_empty: false,
+        ..options
+    };
+    assert!(hash("", serde_wasm_bindgen::to_value(&strict).unwrap()).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_param_fingerprint() {
+    let first = param_fingerprint(&hash_with(2, 16)).unwrap();
+    let second = param_fingerprint(&hash_with(2, 16)).unwrap();
+    let other = param_fingerprint(&hash_with(2, 32)).unwrap();
+
+    assert_eq!(first.len(), 16);
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+}
+
+#[wasm_bindgen_test]
+fn test_pack_hash_round_trip() {
+    let phc = hash_with(2, 16);
+
+    let packed = pack_hash(&phc).unwrap().to_vec();
+    assert!(packed.len() < phc.len());
+    assert_eq!(unpack_hash(&packed).unwrap(), phc);
+
+    assert!(verify_packed(&packed, "test123").unwrap());
+    assert!(!verify_packed(&packed, "wrong_password").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_unpack_hash_rejects_truncated_record() {
+    let packed = pack_hash(&hash_with(1, 16)).unwrap().to_vec();
+
+    let result = unpack_hash(&packed[..packed.len() - 1]);
+    assert!(result.is_err());
+    assert_eq!(
+        error_message(result.unwrap_err()),
+        "Invalid password or hash: Packed hash is truncated"
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_verify_versioned_flags_old_version() {
+    use argon2::password_hash::{PasswordHasher, SaltString};
+
+    let salt = SaltString::from_b64("c29tZXNhbHQ").unwrap();
+    let old_hash = argon2::Argon2::new(
+        argon2::Algorithm::Argon2id,
+        argon2::Version::V0x10,
+        argon2::Params::new(16, 1, 1, None).unwrap(),
+    )
+    .hash_password(b"test123", &salt)
+    .unwrap()
+    .to_string();
+    assert!(old_hash.contains("$v=16$"));
+
+    let result: VersionedVerification =
+        serde_wasm_bindgen::from_value(verify_versioned(&old_hash, "test123", true).unwrap()).unwrap();
+    assert!(result.valid);
+    assert!(result.outdated_version);
+
+    let result: VersionedVerification =
+        serde_wasm_bindgen::from_value(verify_versioned(&hash_with(1, 16), "test123", true).unwrap()).unwrap();
+    assert!(result.valid);
+    assert!(!result.outdated_version);
+}
+
+#[cfg(feature = "test-vectors")]
+#[wasm_bindgen_test]
+fn test_generate_vectors() {
+    let vectors: Vec<argon2id_wasm::TestVector> =
+        serde_wasm_bindgen::from_value(argon2id_wasm::generate_vectors().unwrap()).unwrap();
+    assert!(!vectors.is_empty());
+
+    for vector in &vectors {
+        assert!(verify(&vector.hash, &vector.password).unwrap());
+
+        let parsed = argon2::PasswordHash::new(&vector.hash).unwrap();
+        let params = argon2::Params::try_from(&parsed).unwrap();
+        assert_eq!(params.m_cost(), vector.options.memory_cost);
+        assert_eq!(params.t_cost(), vector.options.time_cost);
+        assert_eq!(params.p_cost(), vector.options.parallelism);
+    }
+
+    // Fixed salts make the vectors reproducible
+    let again: Vec<argon2id_wasm::TestVector> =
+        serde_wasm_bindgen::from_value(argon2id_wasm::generate_vectors().unwrap()).unwrap();
+    assert_eq!(vectors[0].hash, again[0].hash);
+}
+
+#[wasm_bindgen_test]
+fn test_verify_request_matches_verify() {
+    let hash = hash_with(1, 1024);
+    let request = serde_wasm_bindgen::to_value(&VerifyRequest {
+        hash: hash.clone(),
+        password: "test123".to_string(),
+    })
+    .unwrap();
+    assert_eq!(verify_request(request).unwrap(), verify(&hash, "test123").unwrap());
+
+    let wrong = serde_wasm_bindgen::to_value(&VerifyRequest {
+        hash: hash.clone(),
+        password: "wrong".to_string(),
+    })
+    .unwrap();
+    assert_eq!(verify_request(wrong).unwrap(), verify(&hash, "wrong").unwrap());
+
+    assert!(verify_request(JsValue::from_str("not an object")).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_hash_batch_fixed_salt() {
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        ..Default::default()
+    })
+    .unwrap();
+    let passwords = vec!["alpha".to_string(), "beta".to_string(), "alpha".to_string()];
+
+    let hashes = hash_batch_fixed_salt(passwords, b"shared-salt-bytes", options.clone()).unwrap();
+    assert_eq!(hashes.len(), 3);
+    // The trade-off: identical inputs are visible as identical outputs
+    assert_eq!(hashes[0], hashes[2]);
+    assert_ne!(hashes[0], hashes[1]);
+    assert!(verify(&hashes[1], "beta").unwrap());
+
+    let err = hash_batch_fixed_salt(vec!["ok".to_string(), String::new()], b"shared-salt-bytes", options)
+        .unwrap_err();
+    assert!(error_message(err).contains("entry 1"));
+}
+
+#[wasm_bindgen_test]
+fn test_audit_homogeneity() {
+    let id_hash = hash_with(1, 1024);
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        algorithm: Some(Algorithm::Argon2i),
+        ..Default::default()
+    })
+    .unwrap();
+    let i_hash = hash("test123", options).unwrap();
+
+    let uniform: HomogeneityAudit =
+        serde_wasm_bindgen::from_value(audit_homogeneity(vec![
+            id_hash.clone(),
+            hash_with(2, 1024),
+            id_hash.replacen("$v=19", "", 1),
+        ])
+        .unwrap())
+        .unwrap();
+    assert!(uniform.homogeneous);
+    assert_eq!(uniform.algorithms, vec!["argon2id".to_string()]);
+    assert_eq!(uniform.versions, vec![0x13]);
+
+    let mixed: HomogeneityAudit = serde_wasm_bindgen::from_value(
+        audit_homogeneity(vec![id_hash, i_hash, "not a hash".to_string()]).unwrap(),
+    )
+    .unwrap();
+    assert!(!mixed.homogeneous);
+    assert_eq!(mixed.algorithms, vec!["argon2id".to_string(), "argon2i".to_string()]);
+    assert_eq!(mixed.malformed_count, 1);
+}
+
+#[wasm_bindgen_test]
+fn test_verify_metered() {
+    let small: MeteredVerification =
+        serde_wasm_bindgen::from_value(verify_metered(&hash_with(1, 1024), "test123").unwrap()).unwrap();
+    let large: MeteredVerification =
+        serde_wasm_bindgen::from_value(verify_metered(&hash_with(1, 4096), "test123").unwrap()).unwrap();
+
+    assert!(small.valid);
+    assert!(large.valid);
+    assert_eq!(small.compute_units, 1.0);
+    assert!(large.compute_units > small.compute_units);
+
+    let wrong: MeteredVerification =
+        serde_wasm_bindgen::from_value(verify_metered(&hash_with(1, 1024), "wrong").unwrap()).unwrap();
+    assert!(!wrong.valid);
+}
+
+#[wasm_bindgen_test]
+fn test_prewarm_then_hash() {
+    let options = HashOptions {
+        time_cost: 1,
+        memory_cost: 4096,
+        parallelism: 1,
+        ..Default::default()
+    };
+    prewarm(serde_wasm_bindgen::to_value(&options).unwrap()).unwrap();
+
+    let hashed = hash("test123", serde_wasm_bindgen::to_value(&options).unwrap()).unwrap();
+    assert!(verify(&hashed, "test123").unwrap());
+
+    let invalid = HashOptions {
+        memory_cost: 1,
+        ..options
+    };
+    assert!(prewarm(serde_wasm_bindgen::to_value(&invalid).unwrap()).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_salt_len_bounds() {
+    let bounds: ParamBounds = serde_wasm_bindgen::from_value(param_bounds().unwrap()).unwrap();
+    assert_eq!(bounds.salt_len.max, 48);
+
+    let with_salt_len = |salt_len| {
+        serde_wasm_bindgen::to_value(&HashOptions {
+            time_cost: 1,
+            memory_cost: 1024,
+            parallelism: 1,
+            salt_len: Some(salt_len),
+            ..Default::default()
+        })
+        .unwrap()
+    };
+
+    let hashed = hash("test123", with_salt_len(bounds.salt_len.max)).unwrap();
+    assert!(verify(&hashed, "test123").unwrap());
+    let salt = hashed.rsplit('$').nth(1).unwrap();
+    assert_eq!(salt.len(), 64);
+
+    let err = hash("test123", with_salt_len(bounds.salt_len.max + 1)).unwrap_err();
+    let message = error_message(err);
+    assert!(message.contains("at most 48 bytes"), "{}", message);
+}
+
+#[wasm_bindgen_test]
+fn test_verify_min_strength_below_floor() {
+    let weak = hash_with(1, 1024);
+
+    let result: PolicyVerification =
+        serde_wasm_bindgen::from_value(verify_min_strength(&weak, "test123", 2048, 1).unwrap()).unwrap();
+    assert!(result.valid);
+    assert!(result.below_policy);
+
+    let result: PolicyVerification =
+        serde_wasm_bindgen::from_value(verify_min_strength(&weak, "test123", 1024, 2).unwrap()).unwrap();
+    assert!(result.below_policy);
+
+    let result: PolicyVerification =
+        serde_wasm_bindgen::from_value(verify_min_strength(&weak, "wrong", 2048, 1).unwrap()).unwrap();
+    assert!(!result.valid);
+    assert!(result.below_policy);
+}
+
+#[wasm_bindgen_test]
+fn test_verify_min_strength_at_floor() {
+    let strong = hash_with(2, 2048);
+
+    let result: PolicyVerification =
+        serde_wasm_bindgen::from_value(verify_min_strength(&strong, "test123", 2048, 2).unwrap()).unwrap();
+    assert!(result.valid);
+    assert!(!result.below_policy);
+}
+
+#[wasm_bindgen_test]
+fn test_mac_round_trip_and_tamper() {
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        ..Default::default()
+    })
+    .unwrap();
+    let salt = b"mac-salt-bytes";
+    let mut data = b"transfer 100 to alice".to_vec();
+
+    let tag = mac(&data, b"passphrase", salt, options.clone()).unwrap().to_vec();
+    assert_eq!(tag.len(), 32);
+    assert!(mac_verify(&data, &tag, b"passphrase", salt, options.clone()).unwrap());
+    assert!(!mac_verify(&data, &tag, b"other passphrase", salt, options.clone()).unwrap());
+
+    data[0] ^= 0x01;
+    assert!(!mac_verify(&data, &tag, b"passphrase", salt, options).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_param_string_round_trip() {
+    let parsed: HashOptions =
+        serde_wasm_bindgen::from_value(parse_param_string("m=65536,t=3,p=4").unwrap()).unwrap();
+    assert_eq!(parsed.memory_cost, 65536);
+    assert_eq!(parsed.time_cost, 3);
+    assert_eq!(parsed.parallelism, 4);
+
+    let formatted = format_param_string(serde_wasm_bindgen::to_value(&parsed).unwrap()).unwrap();
+    assert_eq!(formatted, "m=65536,t=3,p=4");
+
+    // Order does not matter and whitespace is tolerated
+    let reordered: HashOptions =
+        serde_wasm_bindgen::from_value(parse_param_string("p=4, t=3, m=65536").unwrap()).unwrap();
+    assert_eq!(reordered.memory_cost, 65536);
+}
+
+#[wasm_bindgen_test]
+fn test_param_string_rejects_invalid() {
+    let message = error_message(parse_param_string("m=0,t=3,p=4").unwrap_err());
+    assert!(message.contains("memory_cost"), "{}", message);
+
+    assert!(parse_param_string("m=65536,x=1").is_err());
+    assert!(parse_param_string("m=65536,m=1024").is_err());
+    assert!(parse_param_string("m=lots").is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_verify_legacy_concat() {
+    use base64ct::{Base64, Encoding};
+
+    let options = HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        ..Default::default()
+    };
+    let params = argon2::Params::new(options.memory_cost, options.time_cost, options.parallelism, None).unwrap();
+    let argon2 = argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+
+    let salt = b"legacy-salt-1234";
+    let mut tag = [0u8; 32];
+    argon2.hash_password_into(b"hunter2", salt, &mut tag).unwrap();
+    let stored = format!("{}${}", Base64::encode_string(salt), Base64::encode_string(&tag));
+
+    let js_options = serde_wasm_bindgen::to_value(&options).unwrap();
+    assert!(verify_legacy_concat(&stored, b"hunter2", js_options.clone()).unwrap());
+    assert!(!verify_legacy_concat(&stored, b"hunter3", js_options.clone()).unwrap());
+    assert!(verify_legacy_concat("no-separator", b"hunter2", js_options).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_has_secure_rng() {
+    // Node provides crypto.getRandomValues, so the probe succeeds here
+    let available: bool = has_secure_rng();
+    assert!(available);
+}
+
+#[wasm_bindgen_test]
+fn test_verify_lenient_url_safe_salt() {
+    use argon2::password_hash::{PasswordHasher, SaltString};
+
+    // These bytes encode to '+' and '/' characters in standard base64
+    let salt_bytes = [0xfb, 0xef, 0xbe, 0xff, 0xff, 0xff, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+    let salt = SaltString::encode_b64(&salt_bytes).unwrap();
+    assert!(salt.as_str().contains('+') && salt.as_str().contains('/'));
+
+    let params = argon2::Params::new(1024, 1, 1, None).unwrap();
+    let argon2 = argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+    let standard = argon2.hash_password(b"test123", &salt).unwrap().to_string();
+
+    let url_safe_salt = salt.as_str().replace('+', "-").replace('/', "_");
+    let non_conformant = standard.replace(salt.as_str(), &url_safe_salt);
+
+    assert!(verify(&non_conformant, "test123").is_err());
+    assert!(verify_lenient(&non_conformant, "test123").unwrap());
+    assert!(!verify_lenient(&non_conformant, "wrong").unwrap());
+    assert!(verify_lenient(&standard, "test123").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_hashes_equivalent() {
+    let original = hash_with(1, 1024);
+    assert!(original.contains("m=1024,t=1,p=1"));
+    let reordered = original.replace("m=1024,t=1,p=1", "t=1,p=1,m=1024");
+    assert_ne!(original, reordered);
+
+    assert!(hashes_equivalent(&original, &reordered).unwrap());
+    assert!(!hashes_equivalent(&original, &hash_with(1, 1024)).unwrap());
+
+    // A versionless hash verifies as v=19, so it only matches the v=19 form
+    let versionless = original.replacen("$v=19", "", 1);
+    assert!(verify(&versionless, "test123").unwrap());
+    assert!(hashes_equivalent(&original, &versionless).unwrap());
+    assert!(!hashes_equivalent(&original.replacen("v=19", "v=16", 1), &versionless).unwrap());
+    assert!(hashes_equivalent(&original, "not a hash").is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_hash_hex_round_trip() {
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let hashed = hash_hex("test123", options).unwrap();
+    assert!(hashed.starts_with("$argon2id-hex$v=19$m=1024,t=1,p=1$"));
+    assert!(!hashed.contains(['+', '/']));
+    let encoded = hashed.rsplitn(3, '$').take(2).collect::<Vec<_>>().concat();
+    assert!(encoded.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c)));
+
+    assert!(verify_hex(&hashed, "test123").unwrap());
+    assert!(!verify_hex(&hashed, "wrong").unwrap());
+    assert!(verify_hex(&hash_with(1, 1024), "test123").is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_presets_classify_hashes() {
+    let preset = |time_cost, memory_cost| {
+        serde_wasm_bindgen::to_value(&HashOptions {
+            time_cost,
+            memory_cost,
+            parallelism: 1,
+            ..Default::default()
+        })
+        .unwrap()
+    };
+    register_preset("legacy-2021", preset(3, 1536)).unwrap();
+    register_preset("current-2024", preset(2, 2560)).unwrap();
+
+    let legacy = hash_with(3, 1536);
+    let current = hash_with(2, 2560);
+    let unknown = hash_with(1, 1280);
+
+    assert_eq!(needs_rehash_against_presets(&legacy).unwrap().as_string().unwrap(), "legacy-2021");
+    assert_eq!(needs_rehash_against_presets(&current).unwrap().as_string().unwrap(), "current-2024");
+    assert!(needs_rehash_against_presets(&unknown).unwrap().is_null());
+
+    assert!(register_preset("broken", preset(0, 1024)).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_verify_cached_hit_within_ttl() {
+    let slow = hash_with(3, 32768);
+    let timed = |now_ms: f64| {
+        let start = js_sys::Date::now();
+        assert!(verify_cached(&slow, "test123", 10_000.0, now_ms).unwrap());
+        js_sys::Date::now() - start
+    };
+
+    // Each round starts past the previous entry's TTL, so it misses then hits
+    let (uncached, cached): (Vec<f64>, Vec<f64>) =
+        (0..3).map(|round| f64::from(round) * 20_000.0).map(|now| (timed(now), timed(now + 5_000.0))).unzip();
+    let (uncached_ms, cached_ms) = (median(uncached), median(cached));
+    assert!(cached_ms * 16.0 < uncached_ms, "cached {} ms vs {} ms", cached_ms, uncached_ms);
+
+    // A different password never shares the entry
+    assert!(!verify_cached(&slow, "wrong", 10_000.0, 5_000.0).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_verify_cached_miss_after_expiry() {
+    let slow = hash_with(3, 32768);
+    let timed = |now_ms: f64| {
+        let start = js_sys::Date::now();
+        assert!(verify_cached(&slow, "test123", 1_000.0, now_ms).unwrap());
+        js_sys::Date::now() - start
+    };
+
+    assert!(verify_cached(&slow, "test123", 1_000.0, 1_000.0).unwrap());
+
+    // Each round lands 1.6 s after the last entry was stored, past its 1 s TTL
+    let (expired, cached): (Vec<f64>, Vec<f64>) =
+        (1..4).map(|round| 1_000.0 + f64::from(round) * 1_600.0).map(|now| (timed(now), timed(now + 100.0))).unzip();
+    let (expired_ms, cached_ms) = (median(expired), median(cached));
+    assert!(cached_ms * 16.0 < expired_ms, "cached {} ms vs {} ms", cached_ms, expired_ms);
+}
+
+#[wasm_bindgen_test]
+fn test_effective_password() {
+    let plain = effective_password("p\u{e4}ss", JsValue::UNDEFINED).unwrap().to_vec();
+    assert_eq!(plain, "p\u{e4}ss".as_bytes());
+
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        pad_password_to: Some(16),
+        ..Default::default()
+    })
+    .unwrap();
+    let padded = effective_password("p\u{e4}ss", options).unwrap().to_vec();
+    assert_eq!(padded.len(), 16);
+    assert_eq!(&padded[..4], &5u32.to_be_bytes());
+    assert_eq!(&padded[4..9], "p\u{e4}ss".as_bytes());
+    assert!(padded[9..].iter().all(|&byte| byte == 0));
+}
+
+#[wasm_bindgen_test]
+fn test_pad_password_to_is_capped() {
+    let options = |pad_password_to| {
+        serde_wasm_bindgen::to_value(&HashOptions {
+            pad_password_to: Some(pad_password_to),
+            ..Default::default()
+        })
+        .unwrap()
+    };
+
+    assert_eq!(effective_password("test123", options(4096)).unwrap().to_vec().len(), 4096);
+
+    let err = effective_password("test123", options(4097)).unwrap_err();
+    let message = js_sys::Reflect::get(&err, &JsValue::from_str("message")).unwrap();
+    assert!(message.as_string().unwrap().contains("pad_password_to must be at most 4096"));
+}
+
+#[wasm_bindgen_test]
+fn test_authenticated_params_reject_tampering() {
+    let secret = b"server-side-secret";
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 2048,
+        parallelism: 1,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let stored = hash_authenticated_params("test123", secret, options).unwrap();
+    assert!(verify_authenticated_params(&stored, "test123", secret).unwrap());
+    assert!(!verify_authenticated_params(&stored, "wrong", secret).unwrap());
+
+    let weakened = stored.replace("m=2048", "m=1024");
+    let message = error_message(verify_authenticated_params(&weakened, "test123", secret).unwrap_err());
+    assert!(message.contains("failed authentication"), "{}", message);
+
+    assert!(verify_authenticated_params(&stored, "test123", b"other-secret").is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_verify_streaming_reassembles_chunks() {
+    let hashed = hash_with(1, 1024);
+
+    let chunks = js_sys::Array::of3(
+        &Uint8Array::from(&b"te"[..]),
+        &Uint8Array::from(&b"st1"[..]),
+        &Uint8Array::from(&b"23"[..]),
+    );
+    let next_chunk = js_sys::Function::new_with_args(
+        "chunks",
+        "return () => chunks.length ? chunks.shift() : new Uint8Array(0);",
+    )
+    .call1(&JsValue::NULL, &chunks)
+    .unwrap()
+    .unchecked_into::<js_sys::Function>();
+    assert!(verify_streaming(next_chunk, &hashed).unwrap());
+
+    let empty = js_sys::Function::new_no_args("return new Uint8Array(0);");
+    assert!(verify_streaming(empty, &hashed).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_hash_field_presence() {
+    let standard = hash_with(1, 1024);
+    let presence: FieldPresence = serde_wasm_bindgen::from_value(hash_field_presence(&standard).unwrap()).unwrap();
+    assert!(presence.has_version);
+    assert!(!presence.has_keyid);
+    assert!(!presence.has_data);
+    assert!(presence.has_salt);
+    assert!(presence.has_output);
+
+    let legacy = standard.replace("$v=19", "");
+    let presence: FieldPresence = serde_wasm_bindgen::from_value(hash_field_presence(&legacy).unwrap()).unwrap();
+    assert!(!presence.has_version);
+    assert!(presence.has_output);
+
+    let timestamped = hash_timestamped("test123", JsValue::UNDEFINED, 1_700_000_000.0).unwrap();
+    let presence: FieldPresence =
+        serde_wasm_bindgen::from_value(hash_field_presence(&timestamped).unwrap()).unwrap();
+    assert!(presence.has_data);
+}
+
+#[wasm_bindgen_test]
+fn test_context_domain_separation() {
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        context: Some("appA".to_string()),
+        ..Default::default()
+    })
+    .unwrap();
+    let hashed = hash("test123", options).unwrap();
+
+    let verify_in = |context: Option<&str>| {
+        let options = serde_wasm_bindgen::to_value(&VerifyOptions {
+            context: context.map(str::to_string),
+            ..Default::default()
+        })
+        .unwrap();
+        verify_with_options(&hashed, "test123", options).unwrap()
+    };
+
+    assert!(verify_in(Some("appA")));
+    assert!(!verify_in(Some("appB")));
+    assert!(!verify_in(None));
+    assert!(!verify(&hashed, "test123").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_verify_bounded_rejects_before_allocating() {
+    // 4 GiB of blocks cannot even be allocated in wasm32, so reaching the
+    // Argon2 computation would fail rather than return a rejection
+    let huge = hash_with(1, 1024).replace("m=1024", "m=4194304");
+    let result: BoundedVerification =
+        serde_wasm_bindgen::from_value(verify_bounded(&huge, "test123", 65536).unwrap()).unwrap();
+    assert!(!result.valid);
+    assert!(result.rejected);
+    assert_eq!(result.reason.as_deref(), Some("memory_cost exceeds limit"));
+
+    let result: BoundedVerification =
+        serde_wasm_bindgen::from_value(verify_bounded(&hash_with(1, 1024), "test123", 65536).unwrap()).unwrap();
+    assert!(result.valid);
+    assert!(!result.rejected);
+    assert!(result.reason.is_none());
+
+    let result: BoundedVerification =
+        serde_wasm_bindgen::from_value(verify_bounded(&wrap_credential(&huge), "test123", 65536).unwrap()).unwrap();
+    assert!(result.rejected);
+    let padded = format!("{}\n", hash_with(1, 1024));
+    let result: BoundedVerification =
+        serde_wasm_bindgen::from_value(verify_bounded(&padded, "test123", 65536).unwrap()).unwrap();
+    assert!(result.valid);
+}
+
+#[wasm_bindgen_test]
+fn test_credential_envelope() {
+    let bare = hash_with(1, 1024);
+    let wrapped = wrap_credential(&bare);
+    assert_eq!(wrapped, format!("v1:{}", bare));
+    assert_eq!(unwrap_credential(&wrapped).unwrap(), bare);
+    assert_eq!(unwrap_credential(&bare).unwrap(), bare);
+
+    assert!(verify(&bare, "test123").unwrap());
+    assert!(verify(&wrapped, "test123").unwrap());
+    assert!(!verify(&wrapped, "wrong").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_credential_envelope_unknown_version() {
+    let future = format!("v2:{}", hash_with(1, 1024));
+
+    let message = error_message(unwrap_credential(&future).unwrap_err());
+    assert!(message.contains("Unsupported credential envelope version 'v2'"), "{}", message);
+    assert!(verify(&future, "test123").is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_malformed_segment_error_codes() {
+    let valid = hash_with(1, 1024);
+    let segments: Vec<&str> = valid.split('$').collect();
+    let with_segment = |index: usize, replacement: &str| {
+        let mut corrupted = segments.clone();
+        corrupted[index] = replacement;
+        corrupted.join("$")
+    };
+    let code_for = |hash: &str| {
+        let result: TryVerifyResult = serde_wasm_bindgen::from_value(try_verify(hash, "test123")).unwrap();
+        result.error.unwrap().code
+    };
+
+    assert_eq!(code_for(&with_segment(1, "argon2!d")), "BAD_ALGORITHM");
+    assert_eq!(code_for(&with_segment(1, "scrypt")), "UNSUPPORTED_ALGORITHM");
+    assert_eq!(code_for(&with_segment(3, "m=1024,t=x,p=1")), "BAD_PARAMS");
+    assert_eq!(code_for(&with_segment(4, "c2FsdA!!")), "BAD_SALT");
+    assert_eq!(code_for(&with_segment(5, "not*base64")), "BAD_TAG");
+}
+
+#[wasm_bindgen_test]
+fn test_hash_fields_is_unambiguous() {
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        ..Default::default()
+    })
+    .unwrap();
+    let fields = |values: &[&str]| values.iter().map(|value| value.to_string()).collect::<Vec<_>>();
+
+    let hashed = hash_fields(fields(&["ab", "c"]), options).unwrap();
+    assert!(verify_fields(&hashed, fields(&["ab", "c"])).unwrap());
+    assert!(!verify_fields(&hashed, fields(&["a", "bc"])).unwrap());
+    assert!(!verify_fields(&hashed, fields(&["c", "ab"])).unwrap());
+    assert!(!verify(&hashed, "abc").unwrap());
+
+    assert!(hash_fields(Vec::new(), JsValue::UNDEFINED).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_build_phc_from_raw_components() {
+    let options = HashOptions {
+        time_cost: 2,
+        memory_cost: 1024,
+        parallelism: 1,
+        ..Default::default()
+    };
+    let params = argon2::Params::new(options.memory_cost, options.time_cost, options.parallelism, None).unwrap();
+    let argon2 = argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+
+    let salt = b"raw-record-salt!";
+    let mut tag = [0u8; 32];
+    argon2.hash_password_into(b"hunter2", salt, &mut tag).unwrap();
+
+    let js_options = serde_wasm_bindgen::to_value(&options).unwrap();
+    let phc = build_phc(salt, &tag, js_options.clone()).unwrap();
+    assert!(phc.starts_with("$argon2id$v=19$m=1024,t=2,p=1$"));
+    assert!(verify(&phc, "hunter2").unwrap());
+    assert!(!verify(&phc, "hunter3").unwrap());
+
+    assert!(build_phc(b"short", &tag, js_options.clone()).is_err());
+    assert!(build_phc(salt, &tag[..8], js_options).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_reset_state_clears_globals() {
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1152,
+        parallelism: 1,
+        ..Default::default()
+    })
+    .unwrap();
+    let hashed = hash_with(1, 1152);
+
+    register_preset("reset-me", options.clone()).unwrap();
+    assert!(!needs_rehash_against_presets(&hashed).unwrap().is_null());
+    set_memory_budget(1024.0).unwrap();
+    assert!(hash("test123", options.clone()).is_err());
+
+    reset_state();
+
+    assert!(needs_rehash_against_presets(&hashed).unwrap().is_null());
+    assert!(hash("test123", options).is_ok());
+}
+
+#[wasm_bindgen_test]
+fn test_hash_fixed_width_round_trip() {
+    let options = HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        salt_len: Some(16),
+        output_len: Some(24),
+        ..Default::default()
+    };
+
+    let record = hash_fixed_width("test123", serde_wasm_bindgen::to_value(&options).unwrap())
+        .unwrap()
+        .to_vec();
+    assert_eq!(record.len(), 1 + 1 + 1 + 4 + 4 + 4 + 16 + 24);
+    assert!(verify_fixed_width(&record, "test123").unwrap());
+    assert!(!verify_fixed_width(&record, "wrong").unwrap());
+    assert!(verify_fixed_width(&record[..10], "test123").is_err());
+
+    let unsized_options = HashOptions {
+        output_len: None,
+        ..options
+    };
+    assert!(hash_fixed_width("test123", serde_wasm_bindgen::to_value(&unsized_options).unwrap()).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_verify_identify_argon2id() {
+    let result: IdentifiedVerification =
+        serde_wasm_bindgen::from_value(verify_identify(&hash_with(1, 1024), "wrong").unwrap()).unwrap();
+    assert!(!result.valid);
+    assert_eq!(result.algorithm, "argon2id");
+    assert_eq!(result.version, 0x13);
+
+    let versionless = hash_with(1, 1024).replacen("$v=19", "", 1);
+    let result: IdentifiedVerification =
+        serde_wasm_bindgen::from_value(verify_identify(&wrap_credential(&versionless), "test123").unwrap()).unwrap();
+    assert!(result.valid);
+    assert_eq!(result.version, 0x13);
+}
+
+#[wasm_bindgen_test]
+fn test_verify_identify_argon2i() {
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        algorithm: Some(Algorithm::Argon2i),
+        ..Default::default()
+    })
+    .unwrap();
+    let hashed = hash("test123", options).unwrap();
+
+    let result: IdentifiedVerification =
+        serde_wasm_bindgen::from_value(verify_identify(&hashed, "test123").unwrap()).unwrap();
+    assert!(result.valid);
+    assert_eq!(result.algorithm, "argon2i");
+    assert_eq!(result.version, 0x13);
+}
+
+#[wasm_bindgen_test]
+fn test_verify_rejects_inconsistent_tag_length() {
+    let short = hash(
+        "test123",
+        serde_wasm_bindgen::to_value(&HashOptions {
+            time_cost: 1,
+            memory_cost: 1024,
+            parallelism: 1,
+            output_len: Some(16),
+            ..Default::default()
+        })
+        .unwrap(),
+    )
+    .unwrap();
+    let expecting = |output_len| {
+        serde_wasm_bindgen::to_value(&VerifyOptions {
+            output_len: Some(output_len),
+            ..Default::default()
+        })
+        .unwrap()
+    };
+
+    assert!(verify_with_options(&short, "test123", expecting(16)).unwrap());
+
+    let err = verify_with_options(&short, "test123", expecting(32)).unwrap_err();
+    let code = js_sys::Reflect::get(&err, &JsValue::from_str("code")).unwrap();
+    assert_eq!(code.as_string().unwrap(), "INCONSISTENT_TAG_LENGTH");
+    assert!(error_message(err).contains("expected 32 bytes, hash has 16"));
+}
+
+#[wasm_bindgen_test]
+fn test_hash_adaptive() {
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let adaptive: AdaptiveHash =
+        serde_wasm_bindgen::from_value(hash_adaptive("test123", 20.0, options).unwrap()).unwrap();
+    assert!(adaptive.options_used.time_cost >= 1);
+    assert_eq!(adaptive.options_used.memory_cost, 1024);
+    assert!(adaptive.hash.contains(&format!("t={}", adaptive.options_used.time_cost)));
+    assert!(verify(&adaptive.hash, "test123").unwrap());
+
+    // The probe must size its output from the caller's output_len
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        output_len: Some(64),
+        ..Default::default()
+    })
+    .unwrap();
+    let adaptive: AdaptiveHash =
+        serde_wasm_bindgen::from_value(hash_adaptive("test123", 20.0, options).unwrap()).unwrap();
+    assert_eq!(adaptive.options_used.output_len, Some(64));
+    assert!(verify(&adaptive.hash, "test123").unwrap());
+
+    assert!(hash_adaptive("test123", 0.0, JsValue::UNDEFINED).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_memory_format_round_trip() {
+    let cases = [
+        (512, "512 KiB"),
+        (1536, "1536 KiB"),
+        (19456, "19 MiB"),
+        (65536, "64 MiB"),
+        (1048576, "1 GiB"),
+    ];
+    for (kib, text) in cases {
+        assert_eq!(format_memory(kib), text);
+        assert_eq!(parse_memory(text).unwrap(), kib);
+    }
+
+    assert_eq!(parse_memory("64MiB").unwrap(), 65536);
+    assert_eq!(parse_memory(" 2 gib ").unwrap(), 2 * 1024 * 1024);
+}
+
+#[wasm_bindgen_test]
+fn test_parse_memory_rejects_invalid() {
+    for invalid in ["", "MiB", "64", "64 MB", "1.5 GiB", "-1 KiB", "8192 GiB"] {
+        assert!(parse_memory(invalid).is_err(), "{:?} should not parse", invalid);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_verify_fresh() {
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        ..Default::default()
+    })
+    .unwrap();
+    let created = 1_700_000_000.0;
+    let hashed = hash_timestamped("test123", options, created).unwrap();
+
+    let fresh: FreshVerification =
+        serde_wasm_bindgen::from_value(verify_fresh(&hashed, "test123", 90.0, created + 86_400.0).unwrap()).unwrap();
+    assert!(fresh.valid);
+    assert!(!fresh.expired);
+
+    let aged: FreshVerification =
+        serde_wasm_bindgen::from_value(verify_fresh(&hashed, "test123", 90.0, created + 91.0 * 86_400.0).unwrap())
+            .unwrap();
+    assert!(aged.valid);
+    assert!(aged.expired);
+
+    let wrapped: FreshVerification = serde_wasm_bindgen::from_value(
+        verify_fresh(&wrap_credential(&hashed), "test123", 90.0, created + 91.0 * 86_400.0).unwrap(),
+    )
+    .unwrap();
+    assert!(wrapped.valid);
+    assert!(wrapped.expired);
+
+    assert!(verify_fresh(&hash_with(1, 1024), "test123", 90.0, created).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_hash_out_of_memory_is_catchable() {
+    // Far beyond the 4 GiB wasm32 address space, so allocation always fails
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: u32::MAX,
+        parallelism: 1,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let err = hash("test123", options).unwrap_err();
+    let code = js_sys::Reflect::get(&err, &JsValue::from_str("code")).unwrap();
+    assert_eq!(code.as_string().unwrap(), "RESOURCE_EXHAUSTED");
+    assert!(error_message(err).contains(&format!("{} KiB", u32::MAX)));
+
+    // The module is still usable afterwards
+    assert!(verify(&hash_with(1, 1024), "test123").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_parallelism_auto() {
+    let auto = HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 0,
+        parallelism_auto: true,
+        ..Default::default()
+    };
+    let hashed = hash("test123", serde_wasm_bindgen::to_value(&auto).unwrap()).unwrap();
+    assert!(verify(&hashed, "test123").unwrap());
+
+    let lanes: u32 = hashed
+        .split([',', '$'])
+        .find_map(|field| field.strip_prefix("p="))
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=8).contains(&lanes));
+
+    // Without the flag, zero lanes is still an error
+    let manual = HashOptions {
+        parallelism_auto: false,
+        ..auto
+    };
+    assert!(hash("test123", serde_wasm_bindgen::to_value(&manual).unwrap()).is_err());
+}
+
+fn login_action(hash: &str, password: &str, policy: &LoginPolicy) -> String {
+    let policy = serde_wasm_bindgen::to_value(policy).unwrap();
+    let decision: LoginDecision = serde_wasm_bindgen::from_value(login_check(hash, password, policy).unwrap()).unwrap();
+    assert_eq!(decision.valid, decision.action != "deny");
+    decision.action
+}
+
+#[wasm_bindgen_test]
+fn test_login_check_ok_and_deny() {
+    let current = hash_with(2, 2048);
+    let policy = LoginPolicy {
+        target: Some(HashOptions {
+            time_cost: 2,
+            memory_cost: 2048,
+            parallelism: 1,
+            ..Default::default()
+        }),
+        min_memory_cost: Some(1024),
+        ..Default::default()
+    };
+
+    assert_eq!(login_action(&current, "test123", &policy), "ok");
+    assert_eq!(login_action(&current, "wrong", &policy), "deny");
+}
+
+#[wasm_bindgen_test]
+fn test_login_check_rehash_and_reset() {
+    let policy = LoginPolicy {
+        target: Some(HashOptions {
+            time_cost: 2,
+            memory_cost: 2048,
+            parallelism: 1,
+            ..Default::default()
+        }),
+        min_memory_cost: Some(1024),
+        max_age_days: Some(30.0),
+        now_unix: Some(1_700_000_000.0 + 60.0 * 86_400.0),
+        ..Default::default()
+    };
+
+    // Acceptable but not the target params
+    assert_eq!(login_action(&hash_with(1, 1024), "test123", &policy), "rehash");
+    // Below the floor
+    assert_eq!(login_action(&hash_with(2, 512), "test123", &policy), "reset");
+
+    // Target params, but created 60 days before `now_unix`
+    let options = serde_wasm_bindgen::to_value(policy.target.as_ref().unwrap()).unwrap();
+    let expired = hash_timestamped("test123", options, 1_700_000_000.0).unwrap();
+    assert_eq!(login_action(&expired, "test123", &policy), "reset");
+
+    // The param and age checks see the same normalized hash as verification
+    assert_eq!(login_action(&wrap_credential(&expired), "test123", &policy), "reset");
+    assert_eq!(login_action(&format!("{}\n", hash_with(1, 1024)), "test123", &policy), "rehash");
+}
+
+/// `echo -n "password" | argon2 somesalt -t 2 -m 16 -p 4 -l 24` from the reference CLI's README
+const REFERENCE_CLI_OUTPUT: &str = "Encoded:\t$argon2i$v=19$m=65536,t=2,p=4$c29tZXNhbHQ$RdescudvJCsgt3ub+b+dWRWJTmaaJObG\n";
+
+#[wasm_bindgen_test]
+fn test_verify_reference_format() {
+    assert!(verify_reference_format(REFERENCE_CLI_OUTPUT, "password").unwrap());
+    assert!(!verify_reference_format(REFERENCE_CLI_OUTPUT, "wrong").unwrap());
+
+    let encoded = REFERENCE_CLI_OUTPUT.trim().strip_prefix("Encoded:").unwrap().trim();
+    assert!(verify_reference_format(encoded, "password").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_hash_reference_format_matches_cli_layout() {
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 2,
+        memory_cost: 1024,
+        parallelism: 4,
+        algorithm: Some(Algorithm::Argon2i),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let hashed = hash_reference_format("password", options).unwrap();
+    assert!(hashed.starts_with("$argon2i$v=19$m=1024,t=2,p=4$"));
+    assert!(!hashed.ends_with('='));
+    assert!(verify_reference_format(&hashed, "password").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_hash_with_policy() {
+    let policy = serde_wasm_bindgen::to_value(&PasswordPolicy {
+        min_length: Some(10),
+        require_upper: true,
+        require_digit: true,
+        require_symbol: true,
+    })
+    .unwrap();
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let err = hash_with_policy("abc1", policy.clone(), options.clone()).unwrap_err();
+    let code = js_sys::Reflect::get(&err, &JsValue::from_str("code")).unwrap();
+    assert_eq!(code.as_string().unwrap(), "WEAK_PASSWORD");
+    let message = error_message(err);
+    assert!(message.contains("at least 10 characters"));
+    assert!(message.contains("an uppercase letter"));
+    assert!(message.contains("a symbol"));
+    assert!(!message.contains("a digit"));
+
+    let hashed = hash_with_policy("Correct-horse-9", policy, options).unwrap();
+    assert!(verify(&hashed, "Correct-horse-9").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_verify_hash_bytes() {
+    let hashed = hash_with(1, 1024);
+
+    assert_eq!(
+        verify_hash_bytes(hashed.as_bytes(), "test123").unwrap(),
+        verify(&hashed, "test123").unwrap()
+    );
+    assert!(!verify_hash_bytes(hashed.as_bytes(), "wrong").unwrap());
+
+    let err = verify_hash_bytes(&[0x24, 0xff, 0xfe], "test123").unwrap_err();
+    assert!(error_message(err).contains("not valid UTF-8"));
+}
+
+#[wasm_bindgen_test]
+fn test_hash_namespaced() {
+    let options = || {
+        serde_wasm_bindgen::to_value(&HashOptions {
+            time_cost: 1,
+            memory_cost: 1024,
+            parallelism: 1,
+            ..Default::default()
+        })
+        .unwrap()
+    };
+
+    let first = hash_namespaced(b"user@example.com", b"lookup", options()).unwrap();
+    let second = hash_namespaced(b"user@example.com", b"lookup", options()).unwrap();
+    let other = hash_namespaced(b"user@example.com", b"audit", options()).unwrap();
+
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+    assert!(verify(&first, "user@example.com").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_throughput() {
+    let rate = |memory_cost| {
+        let options = serde_wasm_bindgen::to_value(&HashOptions {
+            time_cost: 1,
+            memory_cost,
+            parallelism: 1,
+            ..Default::default()
+        })
+        .unwrap();
+        throughput(options, 100).unwrap()
+    };
+
+    let cheap = median((0..3).map(|_| rate(1024)).collect());
+    let expensive = median((0..3).map(|_| rate(16384)).collect());
+    assert!(cheap > 0.0 && expensive > 0.0);
+
+    // 16x the memory, so the gap dwarfs scheduling noise
+    assert!(expensive < cheap);
+
+    let err = throughput(JsValue::UNDEFINED, 0).unwrap_err();
+    assert!(error_message(err).contains("at least 1 millisecond"));
+}
+
+#[wasm_bindgen_test]
+fn test_verify_unsupported_algorithm() {
+    let scrypt = "$scrypt$ln=16,r=8,p=1$aM15713r3Xsvxbi31lqr1Q$nFNh2CVHVjNldFVKDHDlm4CbdRSCdEBsjjJxD+iCs5E";
+
+    let err = verify(scrypt, "password").unwrap_err();
+    let code = js_sys::Reflect::get(&err, &JsValue::from_str("code")).unwrap();
+    assert_eq!(code.as_string().unwrap(), "UNSUPPORTED_ALGORITHM");
+    assert!(error_message(err).contains("scrypt"));
+}
+
+#[wasm_bindgen_test]
+fn test_prepended_pepper_round_trip() {
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let hashed = hash_prepended_pepper("test123", b"pepper", options).unwrap();
+    assert!(verify_prepended_pepper(&hashed, "test123", b"pepper").unwrap());
+    assert!(!verify_prepended_pepper(&hashed, "wrong", b"pepper").unwrap());
+    // The documented order makes it a plain Argon2 hash of the concatenation
+    assert!(verify(&hashed, "peppertest123").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_prepended_pepper_wrong_pepper_fails() {
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let hashed = hash_prepended_pepper("test123", b"pepper", options).unwrap();
+    assert!(!verify_prepended_pepper(&hashed, "test123", b"other").unwrap());
+    assert!(verify_prepended_pepper(&hashed, "test123", b"").is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_param_histogram() {
+    let hashes = vec![
+        hash_with(1, 1024),
+        hash_with(1, 1024).replacen("$v=19", "", 1),
+        hash_with(2, 2048),
+        "not a hash".to_string(),
+    ];
+
+    let histogram: ParamHistogram = serde_wasm_bindgen::from_value(param_histogram(hashes).unwrap()).unwrap();
+    assert_eq!(histogram.malformed, 1);
+    assert_eq!(histogram.buckets.len(), 2);
+
+    let common = &histogram.buckets[0];
+    assert_eq!(
+        (common.algorithm.as_str(), common.version, common.memory_cost, common.time_cost, common.parallelism),
+        ("argon2id", 19, 1024, 1, 1)
+    );
+    assert_eq!(common.count, 2);
+
+    let rare = &histogram.buckets[1];
+    assert_eq!((rare.memory_cost, rare.time_cost, rare.count), (2048, 2, 1));
+}
+
+#[wasm_bindgen_test]
+fn test_verify_accepts_hex_and_base64() {
+    let options = || {
+        serde_wasm_bindgen::to_value(&HashOptions {
+            time_cost: 1,
+            memory_cost: 1024,
+            parallelism: 1,
+            ..Default::default()
+        })
+        .unwrap()
+    };
+
+    let base64 = hash("test123", options()).unwrap();
+    let hex = hash_hex("test123", options()).unwrap();
+    assert!(hex.starts_with("$argon2id-hex$"));
+
+    assert!(verify(&base64, "test123").unwrap());
+    assert!(verify(&hex, "test123").unwrap());
+    assert!(!verify(&hex, "wrong").unwrap());
+
+    let unknown = hex.replacen("argon2id-hex", "bcrypt-hex", 1);
+    assert!(error_message(verify(&unknown, "test123").unwrap_err()).contains("Malformed hex hash"));
+}
+
+#[cfg(feature = "fuzz")]
+#[wasm_bindgen_test]
+fn test_hash_with_seed_is_reproducible() {
+    let options = || {
+        serde_wasm_bindgen::to_value(&HashOptions {
+            time_cost: 1,
+            memory_cost: 1024,
+            parallelism: 1,
+            ..Default::default()
+        })
+        .unwrap()
+    };
+
+    let first = argon2id_wasm::hash_with_seed("test123", 42, options()).unwrap();
+    let again = argon2id_wasm::hash_with_seed("test123", 42, options()).unwrap();
+    let other = argon2id_wasm::hash_with_seed("test123", 43, options()).unwrap();
+
+    assert_eq!(first, again);
+    assert_ne!(first, other);
+    assert!(verify(&first, "test123").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_verify_with_external_salt() {
+    use base64ct::{Base64Unpadded, Encoding};
+
+    let hashed = hash_with(1, 1024);
+    let mut segments: Vec<&str> = hashed.split('$').collect();
+    let salt = Base64Unpadded::decode_vec(segments.remove(4)).unwrap();
+    let params_and_tag = segments.join("$");
+
+    for password in ["test123", "wrong"] {
+        assert_eq!(
+            verify_with_external_salt(&params_and_tag, &salt, password).unwrap(),
+            verify(&hashed, password).unwrap()
+        );
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_work_bits_grows_with_time_cost() {
+    let one_pass = work_bits(&hash_with(1, 1024)).unwrap();
+    let two_passes = work_bits(&hash_with(2, 1024)).unwrap();
+
+    assert_eq!(one_pass, 10.0);
+    assert!(two_passes > one_pass);
+    assert_eq!(two_passes - one_pass, 1.0);
+    assert!(work_bits("not a hash").is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_hash_into_object() {
+    let options = || {
+        serde_wasm_bindgen::to_value(&HashOptions {
+            time_cost: 1,
+            memory_cost: 1024,
+            parallelism: 1,
+            ..Default::default()
+        })
+        .unwrap()
+    };
+    let field = |out: &Object, name: &str| js_sys::Reflect::get(out, &JsValue::from_str(name)).unwrap();
+
+    let out = Object::new();
+    hash_into_object("test123", options(), &out).unwrap();
+    let first = field(&out, "hash").as_string().unwrap();
+    assert!(verify(&first, "test123").unwrap());
+    assert_eq!(field(&out, "memory_cost").as_f64(), Some(1024.0));
+    assert!(first.contains(&field(&out, "salt").as_string().unwrap()));
+
+    // Reusing the object overwrites the previous result
+    hash_into_object("other", options(), &out).unwrap();
+    let second = field(&out, "hash").as_string().unwrap();
+    assert_ne!(first, second);
+    assert!(verify(&second, "other").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_verify_encodings_url_match() {
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        ..Default::default()
+    })
+    .unwrap();
+    // A buggy signup path hashed the URL-encoded form of "p@ss word"
+    let hashed = hash("p%40ss%20word", options).unwrap();
+    let encodings = vec!["trim".to_string(), "base64".to_string(), "url".to_string(), "nfc".to_string()];
+
+    let result: EncodingMatch =
+        serde_wasm_bindgen::from_value(verify_encodings(&hashed, "p@ss word", encodings.clone()).unwrap()).unwrap();
+    assert!(result.valid);
+    assert_eq!(result.matched_encoding.as_deref(), Some("url"));
+
+    let miss: EncodingMatch =
+        serde_wasm_bindgen::from_value(verify_encodings(&hashed, "wrong", encodings).unwrap()).unwrap();
+    assert!(!miss.valid);
+    assert!(miss.matched_encoding.is_none());
+
+    let err = verify_encodings(&hashed, "p@ss word", vec!["rot13".to_string()]).unwrap_err();
+    assert!(error_message(err).contains("Unknown password encoding 'rot13'"));
+}
+
+#[wasm_bindgen_test]
+fn test_event_sink_reports_hash_params() {
+    let events = js_sys::Array::new();
+    let sink = js_sys::Function::new_with_args("event", "this.push(event)").bind(&events);
+    set_event_sink(sink);
+
+    let hashed = hash_with(1, 2048);
+    assert!(!verify(&hashed, "wrong").unwrap());
+    clear_event_sink();
+    hash_with(1, 1024);
+
+    let events: Vec<DiagnosticEvent> = serde_wasm_bindgen::from_value(events.into()).unwrap();
+    let completed = events.iter().find(|event| event.event == "hash_completed").unwrap();
+    assert_eq!(completed.memory_cost, 2048);
+    assert!(completed.elapsed_ms.is_some());
+    assert!(events.iter().any(|event| event.event == "verify_mismatch"));
+    // Nothing is recorded once the sink is cleared
+    assert!(events.iter().all(|event| event.memory_cost == 2048));
+}
+
+#[wasm_bindgen_test]
+fn test_error_retryable_flag() {
+    let retryable = |err: &JsValue| js_sys::Reflect::get(err, &JsValue::from_str("retryable")).unwrap().as_bool();
+
+    let rng = JsValue::from(PasswordError::RngUnavailable("crypto.getRandomValues is missing".to_string()));
+    assert_eq!(retryable(&rng), Some(true));
+
+    let malformed = verify("$argon2id$v=19$m=1024,t=1,p=1$c2FsdA!!$AAAA", "test123").unwrap_err();
+    assert_eq!(retryable(&malformed), Some(false));
+}
+
+fn verifier_options() -> JsValue {
+    serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+#[wasm_bindgen_test]
+fn test_verifier_round_trip() {
+    let salt = b"offline-salt-16b";
+
+    let verifier = make_verifier("test123", salt, verifier_options()).unwrap().to_vec();
+    assert_eq!(verifier.len(), 32);
+    assert!(check_verifier(&verifier, "test123", salt, verifier_options()).unwrap());
+    assert!(!check_verifier(&verifier, "wrong", salt, verifier_options()).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_verifier_tamper_fails() {
+    let salt = b"offline-salt-16b";
+    let verifier = make_verifier("test123", salt, verifier_options()).unwrap().to_vec();
+    assert!(!check_verifier(&verifier, "test123", b"another-salt-16b", verifier_options()).unwrap());
+
+    let mut tampered = verifier.clone();
+    tampered[0] ^= 0x01;
+    assert!(!check_verifier(&tampered, "test123", salt, verifier_options()).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_pack_params_round_trip() {
+    for (memory_cost, time_cost, parallelism) in [(1024, 1, 1), (65536, 3, 4), (u32::MAX, 8191, 255), (19456, 2, 1)] {
+        let options = HashOptions {
+            time_cost,
+            memory_cost,
+            parallelism,
+            ..Default::default()
+        };
+        let packed = pack_params(serde_wasm_bindgen::to_value(&options).unwrap()).unwrap();
+        assert!(packed.fract() == 0.0 && packed < 9_007_199_254_740_992.0);
+
+        let unpacked: HashOptions = serde_wasm_bindgen::from_value(unpack_params(packed).unwrap()).unwrap();
+        assert_eq!(
+            (unpacked.memory_cost, unpacked.time_cost, unpacked.parallelism),
+            (memory_cost, time_cost, parallelism)
+        );
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_pack_params_overflow() {
+    let pack = |time_cost, parallelism| {
+        pack_params(
+            serde_wasm_bindgen::to_value(&HashOptions {
+                time_cost,
+                memory_cost: 65536,
+                parallelism,
+                ..Default::default()
+            })
+            .unwrap(),
+        )
+    };
+
+    assert!(error_message(pack(8192, 1).unwrap_err()).contains("time_cost 8192 does not fit in 13 bits"));
+    assert!(error_message(pack(1, 256).unwrap_err()).contains("parallelism 256 does not fit in 8 bits"));
+    assert!(unpack_params(1.5).is_err());
+    assert!(unpack_params(-1.0).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_verify_ignores_surrounding_whitespace() {
+    let hashed = hash_with(1, 1024);
+
+    assert!(verify(&format!("{}\n", hashed), "test123").unwrap());
+    assert!(verify(&format!("  {}\r\n", hashed), "test123").unwrap());
+    assert!(!verify(&format!("{}\n", hashed), "wrong").unwrap());
+    assert!(verify(" \n", "test123").is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_cost_breakdown_tracks_memory() {
+    let breakdown = |memory_cost| -> CostBreakdown {
+        let options = serde_wasm_bindgen::to_value(&HashOptions {
+            time_cost: 3,
+            memory_cost,
+            parallelism: 4,
+            ..Default::default()
+        })
+        .unwrap();
+        serde_wasm_bindgen::from_value(cost_breakdown(options).unwrap()).unwrap()
+    };
+
+    let small = breakdown(19456);
+    let large = breakdown(65536);
+    assert!(large.memory_component > small.memory_component);
+    assert!(large.relative_memory_vs_time > small.relative_memory_vs_time);
+    assert_eq!(large.time_component, small.time_component);
+    assert_eq!(large.parallelism_divisor, 4);
+}
+
+#[wasm_bindgen_test]
+fn test_verify_with_param_override() {
+    // An encoder bug wrote p=2 for a tag computed with p=1
+    let mismatched = hash_with(1, 1024).replacen(",p=1$", ",p=2$", 1);
+    assert!(!verify(&mismatched, "test123").unwrap());
+
+    let param_override = serde_wasm_bindgen::to_value(&ParamOverride {
+        parallelism: Some(1),
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(verify_with_param_override(&mismatched, "test123", param_override.clone()).unwrap());
+    assert!(!verify_with_param_override(&mismatched, "wrong", param_override).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_blind_index_is_deterministic_per_namespace() {
+    let index = |value: &[u8], key: &[u8]| blind_index(value, key, verifier_options()).unwrap().to_vec();
+
+    let first = index(b"user@example.com", b"email-index-key");
+    assert_eq!(first.len(), 32);
+    assert_eq!(first, index(b"user@example.com", b"email-index-key"));
+    assert_ne!(first, index(b"user@example.com", b"other-index-key"));
+    assert_ne!(first, index(b"other@example.com", b"email-index-key"));
+}
+
+#[wasm_bindgen_test]
+fn test_is_native_hash() {
+    let native = hash("test123", JsValue::NULL).unwrap();
+    assert!(is_native_hash(&native));
+
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        output_len: Some(16),
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(!is_native_hash(&hash("test123", options).unwrap()));
+    assert!(!is_native_hash("$argon2i$v=19$m=65536,t=2,p=4$c29tZXNhbHQ$RdescudvJCsgt3ub+b+dWRWJTmaaJObG"));
+    assert!(!is_native_hash("not a hash"));
+}
+
+#[wasm_bindgen_test]
+fn test_verify_with_deadline() {
+    let hashed = hash_with(1, 1024);
+    let clock = js_sys::Function::new_no_args("return 1000");
+    let run = |deadline_ms| -> DeadlineVerification {
+        serde_wasm_bindgen::from_value(verify_with_deadline(&hashed, "test123", deadline_ms, clock.clone()).unwrap())
+            .unwrap()
+    };
+
+    let expired = run(500.0);
+    assert!(!expired.completed);
+    assert!(expired.valid.is_none());
+
+    let in_time = run(5000.0);
+    assert!(in_time.completed);
+    assert_eq!(in_time.valid, Some(true));
+}
+
+#[wasm_bindgen_test]
+fn test_options_json_schema() {
+    let schema = js_sys::JSON::parse(&options_json_schema()).unwrap();
+    let get = |value: &JsValue, key: &str| js_sys::Reflect::get(value, &JsValue::from_str(key)).unwrap();
+
+    let memory_cost = get(&get(&schema, "properties"), "memory_cost");
+    assert!(get(&memory_cost, "minimum").as_f64().unwrap() >= 8.0);
+    assert_eq!(get(&memory_cost, "maximum").as_f64(), Some(f64::from(u32::MAX)));
+    assert!(get(&get(&schema, "properties"), "output_len").is_object());
+
+    // The advertised pad_password_to range is exactly what padding accepts
+    let pad_password_to = get(&get(&schema, "properties"), "pad_password_to");
+    let pads = |pad_password_to: f64| {
+        let options = serde_wasm_bindgen::to_value(&HashOptions {
+            pad_password_to: Some(pad_password_to as u32),
+            ..Default::default()
+        })
+        .unwrap();
+        effective_password("test123", options).is_ok()
+    };
+    let minimum = get(&pad_password_to, "minimum").as_f64().unwrap();
+    let maximum = get(&pad_password_to, "maximum").as_f64().unwrap();
+    assert!(pads(minimum) && pads(maximum));
+    assert!(!pads(minimum - 1.0) && !pads(maximum + 1.0));
+}
+
+#[wasm_bindgen_test]
+fn test_verify_derived_key() {
+    let device_key = [0x5au8; 32];
+    let hashed = hash_prehashed(&device_key, verifier_options()).unwrap();
+
+    assert!(verify_derived(&hashed, &device_key).unwrap());
+    assert!(!verify_derived(&hashed, &[0xa5u8; 32]).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_verify_derived_rejects_wrong_length() {
+    let hashed = hash_prehashed(&[0x5au8; 32], verifier_options()).unwrap();
+
+    let err = verify_derived(&hashed, &[0x5au8; 31]).unwrap_err();
+    assert!(error_message(err).contains("Derived key must be 32 or 64 bytes, got 31"));
+}
+
+#[wasm_bindgen_test]
+fn test_latency_delta_grows_with_memory() {
+    let options = |memory_cost| {
+        serde_wasm_bindgen::to_value(&HashOptions {
+            time_cost: 1,
+            memory_cost,
+            parallelism: 1,
+            ..Default::default()
+        })
+        .unwrap()
+    };
+
+    let deltas: Vec<LatencyDelta> = (0..3)
+        .map(|_| serde_wasm_bindgen::from_value(latency_delta(options(1024), options(32768)).unwrap()).unwrap())
+        .collect();
+    assert!(deltas.iter().all(|delta| delta.old_ms >= 0.0 && delta.new_ms >= 0.0));
+
+    // 32x the memory, so the medians separate regardless of timer noise
+    let old_ms = median(deltas.iter().map(|delta| delta.old_ms).collect());
+    let new_ms = median(deltas.iter().map(|delta| delta.new_ms).collect());
+    assert!(new_ms > old_ms);
+    assert!(median(deltas.iter().map(|delta| delta.factor).collect()) > 1.0);
+}
+
+#[wasm_bindgen_test]
+fn test_options_error_names_the_field() {
+    let options_with = |name: &str, value: JsValue| {
+        let options = serde_wasm_bindgen::to_value(&HashOptions {
+            time_cost: 1,
+            memory_cost: 1024,
+            parallelism: 1,
+            ..Default::default()
+        })
+        .unwrap();
+        js_sys::Reflect::set(&options, &JsValue::from_str(name), &value).unwrap();
+        options
+    };
+
+    let err = hash("test123", options_with("memory_cost", JsValue::from_str("1024"))).unwrap_err();
+    assert!(error_message(err).contains("memory_cost must be a number, got string"));
+
+    let err = hash("test123", options_with("parallelism", JsValue::TRUE)).unwrap_err();
+    assert!(error_message(err).contains("parallelism must be a number, got boolean"));
+
+    let err = hash("test123", options_with("time_cost", JsValue::from_f64(1.5))).unwrap_err();
+    assert!(error_message(err).contains("time_cost must be an integer"));
+}
+
+#[wasm_bindgen_test]
+fn test_derive_key_jwk() {
+    use base64ct::{Base64UrlUnpadded, Encoding};
+
+    let jwk: Jwk =
+        serde_wasm_bindgen::from_value(derive_key_jwk(b"passphrase", b"somesalt", verifier_options()).unwrap()).unwrap();
+    assert_eq!(jwk.kty, "oct");
+    assert_eq!(jwk.alg, "A256GCM");
+    let key = Base64UrlUnpadded::decode_vec(&jwk.k).unwrap();
+    assert_eq!(key.len(), 32);
+    assert_eq!(key, derive_key_for(b"passphrase", b"somesalt", "aes256", verifier_options()).unwrap().to_vec());
+
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        output_len: Some(16),
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(error_message(derive_key_jwk(b"passphrase", b"somesalt", options).unwrap_err()).contains("output_len 16"));
+}
+
+#[wasm_bindgen_test]
+fn test_epoch_flags_rehash() {
+    let epoch_options = |epoch| HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        epoch,
+        ..Default::default()
+    };
+
+    let hashed = hash("test123", serde_wasm_bindgen::to_value(&epoch_options(Some(1))).unwrap()).unwrap();
+    assert!(verify(&hashed, "test123").unwrap());
+    assert_eq!(current_epoch(&hashed).unwrap().as_f64(), Some(1.0));
+    assert!(needs_epoch_rehash(&hashed, 2).unwrap());
+    assert!(!needs_epoch_rehash(&hashed, 1).unwrap());
+
+    let policy = LoginPolicy {
+        target: Some(epoch_options(Some(2))),
+        ..Default::default()
+    };
+    assert_eq!(login_action(&hashed, "test123", &policy), "rehash");
+
+    // The epoch is bound into the tag, so editing it breaks verification
+    let edited = hashed.replacen("data=AAAAAQ", "data=AAAAAg", 1);
+    assert_ne!(edited, hashed);
+    assert!(!verify(&edited, "test123").unwrap());
+
+    let plain = hash_with(1, 1024);
+    assert!(current_epoch(&plain).unwrap().is_null());
+    assert!(needs_epoch_rehash(&plain, 1).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_hash_hex_rejects_epoch() {
+    let options = serde_wasm_bindgen::to_value(&HashOptions {
+        time_cost: 1,
+        memory_cost: 1024,
+        parallelism: 1,
+        epoch: Some(1),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let err = hash_hex("test123", options).unwrap_err();
+    assert!(error_message(err).contains("cannot be hex-encoded"));
+}
+
+/// Whitespace-padded and `v1:`-wrapped spellings of a stored hash
+fn normalized_forms(hashed: &str) -> [String; 2] {
+    [format!("{}\n", hashed), wrap_credential(hashed)]
+}
+
+#[wasm_bindgen_test]
+fn test_verify_helpers_accept_normalized_forms() {
+    let options = HashOptions {
+        time_cost: 2,
+        memory_cost: 2048,
+        parallelism: 1,
+        ..Default::default()
+    };
+    let js_options = || serde_wasm_bindgen::to_value(&options).unwrap();
+    let hashed = hash("test123", js_options()).unwrap();
+    let [padded, wrapped] = normalized_forms(&hashed);
+    let forms = [padded, wrapped, hash_hex("test123", js_options()).unwrap()];
+    let denylist = vec![hashed.clone(), forms[2].clone()];
+
+    for form in &forms {
+        let metered: MeteredVerification = serde_wasm_bindgen::from_value(verify_metered(form, "test123").unwrap()).unwrap();
+        assert!(metered.valid);
+        assert_eq!(metered.compute_units, 4.0);
+
+        let policy: PolicyVerification =
+            serde_wasm_bindgen::from_value(verify_min_strength(form, "test123", 4096, 1).unwrap()).unwrap();
+        assert!(policy.valid && policy.below_policy);
+
+        let versioned: VersionedVerification =
+            serde_wasm_bindgen::from_value(verify_versioned(form, "test123", false).unwrap()).unwrap();
+        assert!(versioned.valid && !versioned.outdated_version);
+
+        let timed: TimedVerification = serde_wasm_bindgen::from_value(verify_timed(form, "test123").unwrap()).unwrap();
+        assert!(timed.valid);
+
+        let identified: IdentifiedVerification =
+            serde_wasm_bindgen::from_value(verify_identify(form, "test123").unwrap()).unwrap();
+        assert!(identified.valid);
+
+        let bounded: BoundedVerification =
+            serde_wasm_bindgen::from_value(verify_bounded(form, "test123", 65536).unwrap()).unwrap();
+        assert!(bounded.valid);
+
+        let login: LoginDecision = serde_wasm_bindgen::from_value(login_check(form, "test123", JsValue::NULL).unwrap()).unwrap();
+        assert_eq!(login.action, "ok");
+
+        let tried: TryVerifyResult = serde_wasm_bindgen::from_value(try_verify(form, "test123")).unwrap();
+        assert!(tried.valid);
+
+        let denylisted: DenylistVerification =
+            serde_wasm_bindgen::from_value(verify_not_compromised(form, "test123", denylist.clone()).unwrap()).unwrap();
+        assert!(denylisted.compromised);
+
+        let encodings: EncodingMatch =
+            serde_wasm_bindgen::from_value(verify_encodings(form, "test123", Vec::new()).unwrap()).unwrap();
+        assert!(encodings.valid);
+
+        let batch: TimedBatchVerification =
+            serde_wasm_bindgen::from_value(verify_batch_timed(verify_requests(&[(form, "test123")])).unwrap()).unwrap();
+        assert_eq!(batch.results, vec![true]);
+
+        assert!(verify_with_options(form, "test123", JsValue::NULL).unwrap());
+        assert!(verify_lenient(form, "test123").unwrap());
+        assert!(verify_with_param_override(form, "test123", JsValue::NULL).unwrap());
+        assert!(verify_cached(form, "test123", 1_000.0, 0.0).unwrap());
+
+        let chunks = js_sys::Array::of1(&Uint8Array::from(&b"test123"[..]));
+        let next_chunk = js_sys::Function::new_with_args(
+            "chunks",
+            "return () => chunks.length ? chunks.shift() : new Uint8Array(0);",
+        )
+        .call1(&JsValue::NULL, &chunks)
+        .unwrap()
+        .unchecked_into::<js_sys::Function>();
+        assert!(verify_streaming(next_chunk, form).unwrap());
+    }
+
+    let digest = [0x5au8; 32];
+    for form in normalized_forms(&hash_prehashed(&digest, js_options()).unwrap()) {
+        assert!(verify_prehashed(&form, &digest).unwrap());
+        assert!(verify_derived(&form, &digest).unwrap());
+    }
+
+    let fields = vec!["alice".to_string(), "test123".to_string()];
+    for form in normalized_forms(&hash_fields(fields.clone(), js_options()).unwrap()) {
+        assert!(verify_fields(&form, fields.clone()).unwrap());
+    }
+
+    for form in normalized_forms(&hash_prepended_pepper("test123", b"pepper", js_options()).unwrap()) {
+        assert!(verify_prepended_pepper(&form, "test123", b"pepper").unwrap());
+    }
+
+    for form in normalized_forms(&hash_hex("test123", js_options()).unwrap()) {
+        assert!(verify_hex(&form, "test123").unwrap());
+    }
+
+    for form in normalized_forms(&hash_authenticated_params("test123", b"secret", js_options()).unwrap()) {
+        assert!(verify_authenticated_params(&form, "test123", b"secret").unwrap());
+    }
+
+    let created = 1_700_000_000.0;
+    for form in normalized_forms(&hash_timestamped("test123", js_options(), created).unwrap()) {
+        let fresh: FreshVerification =
+            serde_wasm_bindgen::from_value(verify_fresh(&form, "test123", 90.0, created).unwrap()).unwrap();
+        assert!(fresh.valid && !fresh.expired);
+    }
+}